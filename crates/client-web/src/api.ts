@@ -62,6 +62,13 @@ export interface BootstrapUser {
   preferred_metadata_languages: string[];
 }
 
+export interface Paginated<T> {
+  items: T[];
+  total: number;
+  limit: number;
+  offset: number;
+}
+
 export interface AppBootstrapResponse {
   has_users: boolean;
   current_user?: BootstrapUser;
@@ -74,6 +81,11 @@ export interface LoginRequest {
 
 export interface TokenResponse {
   token: string;
+  refresh_token: string;
+}
+
+export interface RefreshRequest {
+  refresh_token: string;
 }
 
 export interface ProfileImageUploadRequest {
@@ -533,6 +545,7 @@ export interface PlaybackSession {
   client_profile: ClientProfile;
   decision: PlaybackDecision;
   created_at: number;
+  last_active_at: number;
   audio_stream_index?: number;
 }
 
@@ -644,11 +657,34 @@ export interface SettingsSnapshot {
     cert_path: string;
     key_path: string;
     use_custom_certs: boolean;
+    keep_alive_secs: number;
+    shutdown_grace_secs: number;
+    max_playback_sessions_per_user: number;
+    playback_session_idle_secs: number;
+    idle_shutdown_secs: number;
+    cors_origins: string[];
   };
   ffmpeg: {
     ffmpeg_path: string;
     ffprobe_path: string;
   };
+  api: {
+    jwt_algorithm: 'HS256' | 'RS256';
+    jwt_private_key_path: string;
+    jwt_public_key_path: string;
+    token_ttl_secs: number;
+    refresh_token_ttl_secs: number;
+    refresh_grace_secs: number;
+    bcrypt_cost: number;
+    login_lockout_threshold: number;
+    login_lockout_secs: number;
+    suspicious_login_threshold: number;
+    suspicious_login_window_secs: number;
+    suspicious_login_webhook_url: string | null;
+    username_min_length: number;
+    username_max_length: number;
+    username_restrict_charset: boolean;
+  };
 }
 
 export type ScheduledTaskWeekday =
@@ -916,6 +952,7 @@ function createMockPlaybackSessionResponse<T>(body?: unknown): T {
     client_profile: request.client_profile,
     decision: getMockPlayback(request.item_id),
     created_at: Date.now(),
+    last_active_at: Date.now(),
     audio_stream_index: audioStreamIndex,
   } as T;
 }
@@ -1063,8 +1100,16 @@ export function createUser(request: CreateUserRequest): Promise<string> {
   return requestJson<string>('POST', '/create_user', request);
 }
 
-export function getUsers(): Promise<BootstrapUser[]> {
-  return requestJson<BootstrapUser[]>('GET', '/api/v1/users');
+export async function getUsers(): Promise<BootstrapUser[]> {
+  // The server returns users a page at a time, so keep requesting until every user is loaded.
+  const users: BootstrapUser[] = [];
+  for (;;) {
+    const page = await requestJson<Paginated<BootstrapUser>>('GET', `/api/v1/users?offset=${users.length}`);
+    users.push(...page.items);
+    if (page.items.length === 0 || users.length >= page.total) {
+      return users;
+    }
+  }
 }
 
 export function updateUser(userId: number, request: UpdateUserRequest): Promise<BootstrapUser> {
@@ -773,12 +773,19 @@ export function buildSettingsFromForm(formData: FormData): SettingsSnapshot | un
       use_custom_certs: settingsSection === 'general'
         ? formData.get('use_custom_certs') === 'on'
         : current.server.use_custom_certs,
+      keep_alive_secs: current.server.keep_alive_secs,
+      shutdown_grace_secs: current.server.shutdown_grace_secs,
+      max_playback_sessions_per_user: current.server.max_playback_sessions_per_user,
+      playback_session_idle_secs: current.server.playback_session_idle_secs,
+      idle_shutdown_secs: current.server.idle_shutdown_secs,
+      cors_origins: current.server.cors_origins,
     },
     ffmpeg: {
       ffmpeg_path: formDataString(formData.get('ffmpeg_path'), current.ffmpeg.ffmpeg_path),
       ffprobe_path: formDataString(formData.get('ffprobe_path'), current.ffmpeg.ffprobe_path),
     },
     scheduled_tasks: parseScheduledTasksSettings(formData, current),
+    api: current.api,
   };
 }
 
@@ -16,6 +16,7 @@ import type {
   MediaSearchResult,
   MissingItemsCleanupResponse,
   MetadataProviderStatus,
+  Paginated,
   MetadataPersonItemCredit,
   MetadataPersonResponse,
   MetadataSearchResult,
@@ -851,11 +852,34 @@ let settings: SettingsSnapshot = {
     cert_path: 'cert.pem',
     key_path: 'key.pem',
     use_custom_certs: false,
+    keep_alive_secs: 5,
+    shutdown_grace_secs: 2,
+    max_playback_sessions_per_user: 0,
+    playback_session_idle_secs: 3600,
+    idle_shutdown_secs: 0,
+    cors_origins: [],
   },
   ffmpeg: {
     ffmpeg_path: 'ffmpeg',
     ffprobe_path: 'ffprobe',
   },
+  api: {
+    jwt_algorithm: 'HS256',
+    jwt_private_key_path: 'jwt_private.pem',
+    jwt_public_key_path: 'jwt_public.pem',
+    token_ttl_secs: 86400,
+    refresh_token_ttl_secs: 2592000,
+    refresh_grace_secs: 300,
+    bcrypt_cost: 12,
+    login_lockout_threshold: 10,
+    login_lockout_secs: 300,
+    suspicious_login_threshold: 5,
+    suspicious_login_window_secs: 900,
+    suspicious_login_webhook_url: null,
+    username_min_length: 1,
+    username_max_length: 64,
+    username_restrict_charset: false,
+  },
 };
 
 export function getMockCapabilities(): ServerCapabilities {
@@ -894,7 +918,7 @@ export function loginMockUser(request: LoginRequest): TokenResponse {
   if (!user) {
     throw new Error('401 Unauthorized');
   }
-  return { token: `mock-token-${user.id}` };
+  return { token: `mock-token-${user.id}`, refresh_token: `mock-refresh-token-${user.id}` };
 }
 
 export function createMockUser(request: CreateUserRequest): string {
@@ -927,8 +951,9 @@ export function createMockUser(request: CreateUserRequest): string {
   return 'User created';
 }
 
-export function getMockUsers(): BootstrapUser[] {
-  return users.map(toUserSummary);
+export function getMockUsers(): Paginated<BootstrapUser> {
+  const items = users.map(toUserSummary);
+  return { items, total: items.length, limit: 100, offset: 0 };
 }
 
 export function updateMockUser(userId: number, request: UpdateUserRequest): BootstrapUser {
@@ -953,7 +978,7 @@ export function updateMockUser(userId: number, request: UpdateUserRequest): Boot
     throw new Error('409 Conflict');
   }
   if (user.admin && !request.admin && users.filter((candidate) => candidate.admin).length <= 1) {
-    throw new Error('400 Bad Request');
+    throw new Error('409 Conflict');
   }
 
   user.username = username;
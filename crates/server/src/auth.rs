@@ -1,5 +1,18 @@
 //! Authentication utilities for the application.
 
+// standard imports
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{
+    Arc,
+    Mutex,
+    RwLock,
+};
+use std::time::{
+    Duration,
+    Instant,
+};
+
 // lib imports
 use base64::{
     Engine as _,
@@ -40,8 +53,16 @@ use serde::{
     Deserialize,
     Serialize,
 };
+use sha2::{
+    Digest,
+    Sha256,
+};
 
 // local imports
+use crate::config::{
+    ApiSettings,
+    JwtAlgorithm,
+};
 use crate::db::DbConn;
 
 /// Enum defining different authorization roles
@@ -63,6 +84,396 @@ impl Role {
     }
 }
 
+/// How long a cached admin lookup is trusted before the database is queried again.
+pub const ADMIN_STATUS_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Short-lived cache of `user_id -> admin` lookups used by the admin guard.
+///
+/// Routes that change a user's admin flag must call [`AdminStatusCache::invalidate`] so the
+/// change takes effect on the next request instead of after the TTL.
+pub struct AdminStatusCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<i32, (bool, Instant)>>,
+}
+
+impl AdminStatusCache {
+    /// Create an empty cache whose entries expire after `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Return the cached admin flag for a user, if present and not expired.
+    pub fn get(
+        &self,
+        user_id: i32,
+    ) -> Option<bool> {
+        self.get_at(user_id, Instant::now())
+    }
+
+    /// Return the cached admin flag for a user as of `now`, if present and not expired.
+    pub fn get_at(
+        &self,
+        user_id: i32,
+        now: Instant,
+    ) -> Option<bool> {
+        let mut entries = self.entries.lock().ok()?;
+        match entries.get(&user_id) {
+            Some((is_admin, cached_at)) if now.saturating_duration_since(*cached_at) < self.ttl => {
+                Some(*is_admin)
+            }
+            Some(_) => {
+                entries.remove(&user_id);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Cache the admin flag for a user.
+    pub fn insert(
+        &self,
+        user_id: i32,
+        is_admin: bool,
+    ) {
+        self.insert_at(user_id, is_admin, Instant::now());
+    }
+
+    /// Cache the admin flag for a user as looked up at `now`.
+    pub fn insert_at(
+        &self,
+        user_id: i32,
+        is_admin: bool,
+        now: Instant,
+    ) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(user_id, (is_admin, now));
+        }
+    }
+
+    /// Drop the cached admin flag for a user.
+    pub fn invalidate(
+        &self,
+        user_id: i32,
+    ) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.remove(&user_id);
+        }
+    }
+}
+
+impl Default for AdminStatusCache {
+    fn default() -> Self {
+        Self::new(ADMIN_STATUS_CACHE_TTL)
+    }
+}
+
+/// Smallest size at which [`UsernameTable`] starts pruning expired entries.
+const USERNAME_TABLE_MIN_PRUNE_LEN: usize = 256;
+
+/// Per-username state keyed by submitted login names, which need not exist.
+///
+/// Expired entries are dropped whenever the table has doubled since the last prune, so a
+/// username spray cannot grow it without bound while unexpired entries are always kept.
+struct UsernameTable<V> {
+    entries: HashMap<String, V>,
+    prune_at_len: usize,
+}
+
+impl<V> Default for UsernameTable<V> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            prune_at_len: USERNAME_TABLE_MIN_PRUNE_LEN,
+        }
+    }
+}
+
+impl<V> UsernameTable<V> {
+    /// Drop expired entries if the table has grown enough since the last prune.
+    fn prune_if_grown(
+        &mut self,
+        is_expired: impl Fn(&V) -> bool,
+    ) {
+        if self.entries.len() < self.prune_at_len {
+            return;
+        }
+        self.entries.retain(|_, entry| !is_expired(entry));
+        self.prune_at_len = (self.entries.len() * 2).max(USERNAME_TABLE_MIN_PRUNE_LEN);
+    }
+}
+
+/// Consecutive failed logins for one username and when its lockout ends.
+struct LoginAttempts {
+    failures: u32,
+    last_failure_at: Instant,
+    locked_until: Option<Instant>,
+}
+
+impl LoginAttempts {
+    /// A lockout has ended, or no failure was recorded for `lockout` while unlocked.
+    fn is_expired(
+        &self,
+        now: Instant,
+        lockout: Duration,
+    ) -> bool {
+        match self.locked_until {
+            Some(locked_until) => locked_until <= now,
+            None => now.saturating_duration_since(self.last_failure_at) >= lockout,
+        }
+    }
+}
+
+/// Locks usernames out of login after repeated consecutive failures.
+#[derive(Default)]
+pub struct LoginThrottle {
+    attempts: Mutex<UsernameTable<LoginAttempts>>,
+}
+
+impl LoginThrottle {
+    /// Record a failed login, locking the username for `lockout` once `threshold`
+    /// consecutive failures are reached. A threshold of zero disables lockout.
+    ///
+    /// Failures are forgotten once a lockout ends or after `lockout` passes without another.
+    pub fn record_failure(
+        &self,
+        username: &str,
+        threshold: u32,
+        lockout: Duration,
+    ) {
+        if threshold == 0 {
+            return;
+        }
+        let Ok(mut attempts) = self.attempts.lock() else {
+            return;
+        };
+        let now = Instant::now();
+        attempts.prune_if_grown(|entry| entry.is_expired(now, lockout));
+        let entry = attempts
+            .entries
+            .entry(username.to_string())
+            .or_insert(LoginAttempts {
+                failures: 0,
+                last_failure_at: now,
+                locked_until: None,
+            });
+        if entry.is_expired(now, lockout) {
+            entry.failures = 0;
+            entry.locked_until = None;
+        }
+
+        entry.failures = entry.failures.saturating_add(1);
+        entry.last_failure_at = now;
+        if entry.failures >= threshold {
+            entry.locked_until = Some(now + lockout);
+        }
+    }
+
+    /// Clear failed logins for a username after it signs in successfully.
+    pub fn record_success(
+        &self,
+        username: &str,
+    ) {
+        if let Ok(mut attempts) = self.attempts.lock() {
+            attempts.entries.remove(username);
+        }
+    }
+
+    /// Check whether a username is currently locked out.
+    pub fn is_locked(
+        &self,
+        username: &str,
+    ) -> bool {
+        self.attempts.lock().is_ok_and(|attempts| {
+            attempts
+                .entries
+                .get(username)
+                .and_then(|entry| entry.locked_until)
+                .is_some_and(|locked_until| locked_until > Instant::now())
+        })
+    }
+
+    /// Number of usernames with failed logins still held in memory.
+    pub fn tracked_usernames(&self) -> usize {
+        self.attempts
+            .lock()
+            .map_or(0, |attempts| attempts.entries.len())
+    }
+}
+
+/// Most `suspicious_login` notifications raised across all usernames in one window.
+pub const SUSPICIOUS_LOGIN_NOTIFICATIONS_PER_WINDOW: u32 = 10;
+
+/// Failed logins recorded for one username in the current window.
+struct FailedLoginWindow {
+    started_at: Instant,
+    failures: u32,
+    notified: bool,
+}
+
+/// Notifications raised across all usernames in the current window.
+struct NotificationBudget {
+    started_at: Instant,
+    sent: u32,
+}
+
+/// Per-username windows plus the shared notification budget.
+#[derive(Default)]
+struct SuspiciousLoginState {
+    windows: UsernameTable<FailedLoginWindow>,
+    budget: Option<NotificationBudget>,
+}
+
+/// Counts failed logins per username and decides when to raise a `suspicious_login` event.
+#[derive(Default)]
+pub struct SuspiciousLoginMonitor {
+    state: Mutex<SuspiciousLoginState>,
+}
+
+impl SuspiciousLoginMonitor {
+    /// Record a failed login and return the failure count when it just crossed `threshold`.
+    ///
+    /// Only the first crossing in each `window` is reported, so repeated failures do not
+    /// produce repeated notifications. At most [`SUSPICIOUS_LOGIN_NOTIFICATIONS_PER_WINDOW`]
+    /// crossings are reported per `window` across all usernames.
+    pub fn record_failure(
+        &self,
+        username: &str,
+        threshold: u32,
+        window: Duration,
+    ) -> Option<u32> {
+        if threshold == 0 {
+            return None;
+        }
+        let mut state = self.state.lock().ok()?;
+        let now = Instant::now();
+        state
+            .windows
+            .prune_if_grown(|entry| now.saturating_duration_since(entry.started_at) >= window);
+        let entry =
+            state
+                .windows
+                .entries
+                .entry(username.to_string())
+                .or_insert(FailedLoginWindow {
+                    started_at: now,
+                    failures: 0,
+                    notified: false,
+                });
+        if now.saturating_duration_since(entry.started_at) >= window {
+            *entry = FailedLoginWindow {
+                started_at: now,
+                failures: 0,
+                notified: false,
+            };
+        }
+
+        entry.failures = entry.failures.saturating_add(1);
+        if entry.failures < threshold || entry.notified {
+            return None;
+        }
+        entry.notified = true;
+        let failures = entry.failures;
+
+        let budget = state.budget.get_or_insert(NotificationBudget {
+            started_at: now,
+            sent: 0,
+        });
+        if now.saturating_duration_since(budget.started_at) >= window {
+            *budget = NotificationBudget {
+                started_at: now,
+                sent: 0,
+            };
+        }
+        if budget.sent >= SUSPICIOUS_LOGIN_NOTIFICATIONS_PER_WINDOW {
+            log::warn!(
+                "Suppressing suspicious_login notification for '{}': limit of {} per window \
+                 reached",
+                username,
+                SUSPICIOUS_LOGIN_NOTIFICATIONS_PER_WINDOW
+            );
+            return None;
+        }
+        budget.sent += 1;
+        Some(failures)
+    }
+
+    /// Forget failed logins for a username after it signs in successfully.
+    pub fn record_success(
+        &self,
+        username: &str,
+    ) {
+        if let Ok(mut state) = self.state.lock() {
+            state.windows.entries.remove(username);
+        }
+    }
+
+    /// Number of usernames with failed logins still held in memory.
+    pub fn tracked_usernames(&self) -> usize {
+        self.state
+            .lock()
+            .map_or(0, |state| state.windows.entries.len())
+    }
+}
+
+/// Webhook payload sent when a username crosses the failed-login threshold.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct SuspiciousLoginEvent {
+    /// Always `suspicious_login`.
+    pub event: &'static str,
+    /// Username that failed to sign in.
+    pub username: String,
+    /// Client address of the request that crossed the threshold, when known.
+    pub source_ip: Option<String>,
+    /// Failed logins counted in the current window.
+    pub failed_attempts: u32,
+}
+
+impl SuspiciousLoginEvent {
+    /// Build a `suspicious_login` event.
+    pub fn new(
+        username: &str,
+        source_ip: Option<std::net::IpAddr>,
+        failed_attempts: u32,
+    ) -> Self {
+        Self {
+            event: "suspicious_login",
+            username: username.to_string(),
+            source_ip: source_ip.map(|ip| ip.to_string()),
+            failed_attempts,
+        }
+    }
+}
+
+/// Log a suspicious login and deliver it to the configured webhook, if any.
+pub async fn notify_suspicious_login(
+    event: SuspiciousLoginEvent,
+    webhook_url: Option<String>,
+) {
+    log::warn!(
+        "suspicious_login: {} failed logins for {:?} (last from {})",
+        event.failed_attempts,
+        event.username,
+        event.source_ip.as_deref().unwrap_or("unknown address")
+    );
+
+    let Some(webhook_url) = webhook_url.filter(|url| !url.trim().is_empty()) else {
+        return;
+    };
+    let result = reqwest::Client::new()
+        .post(webhook_url.trim())
+        .timeout(Duration::from_secs(10))
+        .json(&event)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status());
+    if let Err(error) = result {
+        log::warn!("Failed to deliver suspicious_login webhook: {}", error);
+    }
+}
+
 /// Generic authorization guard that can handle different roles
 pub struct AuthGuard<const ROLE: u8> {
     claims: Claims,
@@ -108,24 +519,40 @@ impl<'r, const ROLE: u8> FromRequest<'r> for AuthGuard<ROLE> {
         }
 
         // For other roles, we need to check additional permissions
-        let db = match request.guard::<DbConn>().await {
-            Outcome::Success(db) => db,
-            _ => return Outcome::Error((Status::InternalServerError, ())),
-        };
-
         let user_id: i32 = match claims.sub.parse() {
             Ok(id) => id,
             Err(_) => return Outcome::Error((Status::Unauthorized, ())),
         };
 
         let has_permission = match role {
-            Role::Admin => db
-                .run(move |conn| {
-                    use crate::db::schema::users::dsl::*;
-                    users.find(user_id).select(admin).first::<bool>(conn)
-                })
-                .await
-                .unwrap_or(false),
+            Role::Admin => {
+                let cache = request.rocket().state::<AdminStatusCache>();
+                match cache.and_then(|cache| cache.get(user_id)) {
+                    Some(is_admin) => is_admin,
+                    None => {
+                        // Only a cache miss needs a pooled connection.
+                        let db = match request.guard::<DbConn>().await {
+                            Outcome::Success(db) => db,
+                            _ => return Outcome::Error((Status::InternalServerError, ())),
+                        };
+                        let lookup = db
+                            .run(move |conn| {
+                                use crate::db::schema::users::dsl::*;
+                                users.find(user_id).select(admin).first::<bool>(conn)
+                            })
+                            .await;
+                        match lookup {
+                            Ok(is_admin) => {
+                                if let Some(cache) = cache {
+                                    cache.insert(user_id, is_admin);
+                                }
+                                is_admin
+                            }
+                            Err(_) => false,
+                        }
+                    }
+                }
+            }
             Role::User => true, // Already handled above
         };
 
@@ -176,6 +603,17 @@ impl<const ROLE: u8> OpenApiFromRequest<'_> for AuthGuard<ROLE> {
     }
 }
 
+/// Kind of JWT, so refresh tokens cannot be used to call the API.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenType {
+    /// Short-lived token accepted by authenticated routes.
+    #[default]
+    Access,
+    /// Long-lived token only accepted by `/refresh`.
+    Refresh,
+}
+
 /// Claims for the JWT.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Claims {
@@ -183,30 +621,175 @@ pub struct Claims {
     pub sub: String,
     /// Expiration time as Unix timestamp
     pub exp: usize,
+    /// Kind of token; tokens issued before this field existed are access tokens.
+    #[serde(default)]
+    pub token_type: TokenType,
+    /// Fingerprint of the user's credentials when a refresh token was issued; changing the
+    /// password or PIN revokes every refresh token that carries the old fingerprint.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential: Option<String>,
 }
 
-const BEARER: &str = "Bearer ";
+const BEARER: &str = "Bearer";
+
+/// Signing and verification keys for API access tokens.
+#[derive(Clone)]
+pub struct JwtKeys {
+    algorithm: Algorithm,
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    previous_decoding: Vec<DecodingKey>,
+}
+
+impl JwtKeys {
+    /// Build HS256 keys from a shared secret.
+    pub fn hmac(secret: &str) -> Self {
+        Self::hmac_with_previous(secret, &[])
+    }
+
+    /// Build HS256 keys that sign with `secret` and also accept tokens signed with any of
+    /// `previous_secrets`, so a secret can be rotated without logging everyone out.
+    pub fn hmac_with_previous(
+        secret: &str,
+        previous_secrets: &[String],
+    ) -> Self {
+        Self {
+            algorithm: Algorithm::HS256,
+            encoding: EncodingKey::from_secret(secret.as_ref()),
+            decoding: DecodingKey::from_secret(secret.as_ref()),
+            previous_decoding: previous_secrets
+                .iter()
+                .map(|previous| DecodingKey::from_secret(previous.as_ref()))
+                .collect(),
+        }
+    }
+
+    /// Build RS256 keys from PEM-encoded private and public keys.
+    pub fn rsa_pem(
+        private_key_pem: &[u8],
+        public_key_pem: &[u8],
+    ) -> Result<Self, jsonwebtoken::errors::Error> {
+        Ok(Self {
+            algorithm: Algorithm::RS256,
+            encoding: EncodingKey::from_rsa_pem(private_key_pem)?,
+            decoding: DecodingKey::from_rsa_pem(public_key_pem)?,
+            previous_decoding: Vec::new(),
+        })
+    }
+
+    /// Build keys for the configured algorithm, reading RS256 key files from disk.
+    pub fn from_settings(settings: &ApiSettings) -> Result<Self, String> {
+        match settings.jwt_algorithm {
+            JwtAlgorithm::Hs256 => {
+                let (secret, previous_secrets) = crate::config::resolve_api_jwt_secrets(settings)?;
+                Ok(Self::hmac_with_previous(
+                    secret.as_deref().unwrap_or(get_jwt_secret()),
+                    &previous_secrets,
+                ))
+            }
+            JwtAlgorithm::Rs256 => {
+                let private_key_pem =
+                    fs::read(&settings.jwt_private_key_path).map_err(|error| {
+                        format!(
+                            "Failed to read JWT private key {}: {error}",
+                            settings.jwt_private_key_path
+                        )
+                    })?;
+                let public_key_pem = fs::read(&settings.jwt_public_key_path).map_err(|error| {
+                    format!(
+                        "Failed to read JWT public key {}: {error}",
+                        settings.jwt_public_key_path
+                    )
+                })?;
+                Self::rsa_pem(&private_key_pem, &public_key_pem)
+                    .map_err(|error| format!("Failed to parse RS256 JWT keys: {error}"))
+            }
+        }
+    }
+
+    /// Get the signing algorithm for these keys.
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+}
 
 /// Create a JWT token.
 pub fn create_token(
     user_id: &str,
     secret: &str,
 ) -> Result<String, jsonwebtoken::errors::Error> {
+    create_token_with_keys(user_id, &JwtKeys::hmac(secret))
+}
+
+/// Create a JWT token signed with the given keys that expires after `api.token_ttl_secs`.
+pub fn create_token_with_keys(
+    user_id: &str,
+    keys: &JwtKeys,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    let ttl = Duration::from_secs(crate::config::current_settings().api.token_ttl_secs);
+    create_token_with_ttl(user_id, keys, ttl)
+}
+
+/// Create a JWT token signed with the given keys that expires after `ttl`.
+pub fn create_token_with_ttl(
+    user_id: &str,
+    keys: &JwtKeys,
+    ttl: Duration,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    encode_claims(user_id, None, keys, ttl, TokenType::Access)
+}
+
+/// Create a refresh token signed with the given keys that expires after
+/// `api.refresh_token_ttl_secs`.
+///
+/// `credential` should come from [`credential_fingerprint`] so `/refresh` can reject the token
+/// once the user's password or PIN changes.
+pub fn create_refresh_token(
+    user_id: &str,
+    credential: &str,
+    keys: &JwtKeys,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    let ttl = Duration::from_secs(crate::config::current_settings().api.refresh_token_ttl_secs);
+    encode_claims(user_id, Some(credential), keys, ttl, TokenType::Refresh)
+}
+
+/// Fingerprint a user's stored password and PIN hashes for refresh token revocation.
+pub fn credential_fingerprint(
+    password_hash: &str,
+    pin_hash: Option<&str>,
+) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(password_hash.as_bytes());
+    hasher.update([0]);
+    hasher.update(pin_hash.unwrap_or_default().as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn encode_claims(
+    user_id: &str,
+    credential: Option<&str>,
+    keys: &JwtKeys,
+    ttl: Duration,
+    token_type: TokenType,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    let ttl = chrono::Duration::from_std(ttl).unwrap_or(chrono::Duration::MAX);
     let expiration = chrono::Utc::now()
-        .checked_add_signed(chrono::Duration::hours(24))
-        .expect("valid timestamp")
+        .checked_add_signed(ttl)
+        .unwrap_or(chrono::DateTime::<chrono::Utc>::MAX_UTC)
         .timestamp();
 
     let claims = Claims {
         sub: user_id.to_owned(),
         exp: expiration as usize,
+        token_type,
+        credential: credential.map(str::to_owned),
     };
 
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_ref()),
-    )
+    encode(&Header::new(keys.algorithm), &claims, &keys.encoding)
 }
 
 /// Decode a JWT token.
@@ -214,15 +797,92 @@ pub fn decode_token(
     token: &str,
     secret: &str,
 ) -> Result<Claims, jsonwebtoken::errors::Error> {
-    let mut validation = Validation::new(Algorithm::HS256);
+    decode_token_with_keys(token, &JwtKeys::hmac(secret))
+}
+
+/// Decode an access token verified with the given keys.
+///
+/// The current key is tried first, then any previous keys still accepted during a rotation.
+/// Refresh tokens are rejected.
+pub fn decode_token_with_keys(
+    token: &str,
+    keys: &JwtKeys,
+) -> Result<Claims, jsonwebtoken::errors::Error> {
+    let validation = Validation::new(keys.algorithm);
+    decode_claims(token, keys, validation, TokenType::Access)
+}
+
+/// Decode a refresh token verified with the given keys.
+///
+/// Tokens that expired less than `grace` ago are still accepted. Access tokens are rejected.
+pub fn decode_refresh_token(
+    token: &str,
+    keys: &JwtKeys,
+    grace: Duration,
+) -> Result<Claims, jsonwebtoken::errors::Error> {
+    let mut validation = Validation::new(keys.algorithm);
+    validation.leeway = grace.as_secs();
+    decode_claims(token, keys, validation, TokenType::Refresh)
+}
+
+fn decode_claims(
+    token: &str,
+    keys: &JwtKeys,
+    mut validation: Validation,
+    token_type: TokenType,
+) -> Result<Claims, jsonwebtoken::errors::Error> {
     validation.set_required_spec_claims(&["exp", "sub"]);
+    let claims = decode_with_rotation(token, keys, &validation)?;
+    if claims.token_type != token_type {
+        return Err(jsonwebtoken::errors::ErrorKind::InvalidToken.into());
+    }
+    Ok(claims)
+}
+
+fn decode_with_rotation(
+    token: &str,
+    keys: &JwtKeys,
+    validation: &Validation,
+) -> Result<Claims, jsonwebtoken::errors::Error> {
+    let current = decode::<Claims>(token, &keys.decoding, validation);
+    if current.is_ok() {
+        return current.map(|data| data.claims);
+    }
+    keys.previous_decoding
+        .iter()
+        .find_map(|decoding| decode::<Claims>(token, decoding, validation).ok())
+        .map(|data| data.claims)
+        .ok_or_else(|| current.unwrap_err())
+}
+
+type CachedJwtKeys = Option<(ApiSettings, Arc<JwtKeys>)>;
 
-    decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(secret.as_ref()),
-        &validation,
-    )
-    .map(|data| data.claims)
+static ACTIVE_JWT_KEYS: Lazy<RwLock<CachedJwtKeys>> = Lazy::new(|| RwLock::new(None));
+
+/// Return the JWT keys for the current API settings, reloading them when the settings change.
+pub fn active_jwt_keys() -> Result<Arc<JwtKeys>, String> {
+    let settings = crate::config::current_settings().api;
+    if let Ok(cached) = ACTIVE_JWT_KEYS.read() {
+        if let Some((cached_settings, keys)) = cached.as_ref() {
+            if *cached_settings == settings {
+                return Ok(keys.clone());
+            }
+        }
+    }
+
+    let keys = Arc::new(JwtKeys::from_settings(&settings)?);
+    if let Ok(mut cached) = ACTIVE_JWT_KEYS.write() {
+        *cached = Some((settings, keys.clone()));
+    }
+    Ok(keys)
+}
+
+/// Drop the cached JWT keys so the next request reloads them, e.g. after the stored secrets
+/// change without the secret-store references changing.
+pub(crate) fn reset_active_jwt_keys() {
+    if let Ok(mut cached) = ACTIVE_JWT_KEYS.write() {
+        *cached = None;
+    }
 }
 
 #[rocket::async_trait]
@@ -230,25 +890,46 @@ impl<'r> FromRequest<'r> for Claims {
     type Error = ();
 
     async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
-        let keys: Vec<_> = request.headers().get("Authorization").collect();
-        if keys.len() != 1 {
+        // Proxies can duplicate the header, so accept the first credential that verifies.
+        let tokens: Vec<_> = request
+            .headers()
+            .get("Authorization")
+            .filter_map(bearer_token)
+            .collect();
+        if tokens.is_empty() {
             return Outcome::Error((rocket::http::Status::Unauthorized, ()));
         }
 
-        if !keys[0].starts_with(BEARER) {
-            return Outcome::Error((rocket::http::Status::Unauthorized, ()));
-        }
+        let jwt_keys = match active_jwt_keys() {
+            Ok(jwt_keys) => jwt_keys,
+            Err(error) => {
+                log::error!("Failed to load JWT keys: {}", error);
+                return Outcome::Error((rocket::http::Status::InternalServerError, ()));
+            }
+        };
 
-        let token = &keys[0][BEARER.len()..];
-        let secret = get_jwt_secret();
+        let claims = tokens
+            .into_iter()
+            .find_map(|token| decode_token_with_keys(token, &jwt_keys).ok());
 
-        match decode_token(token, secret) {
-            Ok(claims) => Outcome::Success(claims),
-            Err(_) => Outcome::Error((rocket::http::Status::Unauthorized, ())),
+        match claims {
+            Some(claims) => Outcome::Success(claims),
+            None => Outcome::Error((rocket::http::Status::Unauthorized, ())),
         }
     }
 }
 
+/// Extract the token from a `Bearer` Authorization header value, matching the scheme
+/// case-insensitively.
+fn bearer_token(header: &str) -> Option<&str> {
+    let (scheme, token) = header.trim().split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case(BEARER) {
+        return None;
+    }
+    let token = token.trim();
+    (!token.is_empty()).then_some(token)
+}
+
 static JWT_SECRET: Lazy<String> = Lazy::new(|| {
     let random_bytes: [u8; 32] = rand::rng().random();
     general_purpose::STANDARD.encode(random_bytes)
@@ -258,9 +939,24 @@ pub(crate) fn get_jwt_secret() -> &'static str {
     &JWT_SECRET
 }
 
-/// Hash a password using BCrypt (handles salting internally)
+/// Hash a password using BCrypt (handles salting internally) with the configured `api.bcrypt_cost`
 pub fn hash_password(password: &str) -> Result<String, bcrypt::BcryptError> {
-    hash(password, DEFAULT_COST)
+    hash_password_with_cost(password, crate::config::current_settings().api.bcrypt_cost)
+}
+
+/// Hash a password using BCrypt with `cost`, falling back to the default cost when it is outside
+/// the 4–31 range BCrypt accepts.
+pub fn hash_password_with_cost(
+    password: &str,
+    cost: u32,
+) -> Result<String, bcrypt::BcryptError> {
+    let cost = if (4..=31).contains(&cost) {
+        cost
+    } else {
+        log::warn!("Invalid bcrypt cost {}, using {}", cost, DEFAULT_COST);
+        DEFAULT_COST
+    };
+    hash(password, cost)
 }
 
 /// Verify a password against a BCrypt hash
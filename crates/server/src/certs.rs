@@ -7,8 +7,57 @@ use std::path::Path;
 // lib imports
 use rcgen::{
     CertifiedKey,
+    KeyPair,
+    PublicKeyData,
     generate_simple_self_signed,
 };
+use schemars::JsonSchema;
+use serde::Serialize;
+use x509_parser::extensions::GeneralName;
+use x509_parser::pem::parse_x509_pem;
+
+// local imports
+use crate::config::Settings;
+
+/// Details read from a certificate whose private key matched.
+#[derive(Debug, Clone, Serialize, JsonSchema, PartialEq, Eq)]
+pub struct CertificateSummary {
+    /// Certificate subject distinguished name.
+    pub subject: String,
+    /// DNS names and IP addresses listed in the subject alternative name extension.
+    pub subject_alt_names: Vec<String>,
+    /// Expiry as a Unix timestamp in seconds.
+    pub not_after: i64,
+    /// Whole days left before the certificate expires; negative once it has expired.
+    pub days_until_expiry: i64,
+    /// Whether the certificate has already expired.
+    pub expired: bool,
+}
+
+/// Whole days from `now` until a certificate that is valid through `not_after` expires.
+///
+/// Both timestamps are Unix seconds. The result is negative once the certificate has expired.
+pub fn days_until_expiry(
+    not_after: i64,
+    now: i64,
+) -> i64 {
+    (not_after - now).div_euclid(24 * 60 * 60)
+}
+
+/// Return the certificate and key paths used by the web server for these settings.
+pub fn certificate_paths(settings: &Settings) -> (String, String) {
+    if settings.server.use_custom_certs {
+        (
+            settings.server.cert_path.clone(),
+            settings.server.key_path.clone(),
+        )
+    } else {
+        (
+            format!("{}/cert.pem", settings.general.data_dir),
+            format!("{}/key.pem", settings.general.data_dir),
+        )
+    }
+}
 
 /// Ensure that the certificates exist at the given paths.
 pub fn ensure_certificates_exist(
@@ -32,3 +81,68 @@ pub fn ensure_certificates_exist(
         fs::write(key_path, signing_key.serialize_pem()).expect("Failed to write private key");
     }
 }
+
+/// Load a PEM certificate and private key, verify that they belong together, and summarize the
+/// certificate.
+pub fn inspect_certificate_pair(
+    cert_path: &str,
+    key_path: &str,
+) -> Result<CertificateSummary, String> {
+    let cert_pem = fs::read(cert_path)
+        .map_err(|error| format!("Failed to read certificate {cert_path}: {error}"))?;
+    let key_pem = fs::read_to_string(key_path)
+        .map_err(|error| format!("Failed to read private key {key_path}: {error}"))?;
+
+    let (_, pem) = parse_x509_pem(&cert_pem)
+        .map_err(|error| format!("Failed to parse certificate PEM {cert_path}: {error}"))?;
+    let cert = pem
+        .parse_x509()
+        .map_err(|error| format!("Failed to parse certificate {cert_path}: {error}"))?;
+    let key_pair = KeyPair::from_pem(&key_pem)
+        .map_err(|error| format!("Failed to parse private key {key_path}: {error}"))?;
+
+    if cert.public_key().subject_public_key.as_ref() != key_pair.der_bytes() {
+        return Err(format!(
+            "Private key {key_path} does not match certificate {cert_path}"
+        ));
+    }
+
+    let subject_alt_names = cert
+        .subject_alternative_name()
+        .map_err(|error| format!("Failed to read subject alternative names: {error}"))?
+        .map(|extension| {
+            extension
+                .value
+                .general_names
+                .iter()
+                .filter_map(|name| match name {
+                    GeneralName::DNSName(name) => Some(name.to_string()),
+                    GeneralName::IPAddress(bytes) => ip_address_from_bytes(bytes),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let not_after = cert.validity().not_after.timestamp();
+    let days_until_expiry = days_until_expiry(not_after, crate::utils::current_timestamp());
+
+    Ok(CertificateSummary {
+        subject: cert.subject().to_string(),
+        subject_alt_names,
+        not_after,
+        days_until_expiry,
+        expired: days_until_expiry < 0,
+    })
+}
+
+fn ip_address_from_bytes(bytes: &[u8]) -> Option<String> {
+    match bytes.len() {
+        4 => <[u8; 4]>::try_from(bytes)
+            .ok()
+            .map(|octets| std::net::Ipv4Addr::from(octets).to_string()),
+        16 => <[u8; 16]>::try_from(bytes)
+            .ok()
+            .map(|octets| std::net::Ipv6Addr::from(octets).to_string()),
+        _ => None,
+    }
+}
@@ -30,6 +30,7 @@ const MEDIA_SETTINGS_KEY: &str = "media";
 const SERVER_SETTINGS_KEY: &str = "server";
 const FFMPEG_SETTINGS_KEY: &str = "ffmpeg";
 const SCHEDULED_TASKS_SETTINGS_KEY: &str = "scheduled_tasks";
+const API_SETTINGS_KEY: &str = "api";
 
 /// General settings.
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
@@ -138,6 +139,70 @@ fn default_recursive_scan() -> bool {
     true
 }
 
+fn default_keep_alive_secs() -> u32 {
+    5
+}
+
+fn default_shutdown_grace_secs() -> u32 {
+    2
+}
+
+fn default_max_playback_sessions_per_user() -> u32 {
+    0
+}
+
+fn default_playback_session_idle_secs() -> u64 {
+    60 * 60
+}
+
+fn default_jwt_private_key_path() -> String {
+    "jwt_private.pem".into()
+}
+
+fn default_jwt_public_key_path() -> String {
+    "jwt_public.pem".into()
+}
+
+fn default_token_ttl_secs() -> u64 {
+    86_400
+}
+
+fn default_refresh_token_ttl_secs() -> u64 {
+    2_592_000
+}
+
+fn default_refresh_grace_secs() -> u64 {
+    300
+}
+
+fn default_bcrypt_cost() -> u32 {
+    bcrypt::DEFAULT_COST
+}
+
+fn default_login_lockout_threshold() -> u32 {
+    10
+}
+
+fn default_login_lockout_secs() -> u64 {
+    300
+}
+
+fn default_suspicious_login_threshold() -> u32 {
+    5
+}
+
+fn default_suspicious_login_window_secs() -> u64 {
+    900
+}
+
+fn default_username_min_length() -> u32 {
+    1
+}
+
+fn default_username_max_length() -> u32 {
+    64
+}
+
 fn default_ffmpeg_path() -> String {
     "ffmpeg".into()
 }
@@ -488,6 +553,9 @@ fn metadata_provider_api_key_secret_ref(provider_id: &MetadataProviderId) -> Str
     )
 }
 
+const JWT_SECRET_REF: &str = "api:jwt-secret";
+const JWT_PREVIOUS_SECRETS_REF: &str = "api:jwt-previous-secrets";
+
 fn normalized_secret_value(value: Option<&String>) -> Option<String> {
     value
         .map(|value| value.trim().to_string())
@@ -669,6 +737,108 @@ pub struct ServerSettings {
     /// Use custom certs.
     #[serde(default)]
     pub use_custom_certs: bool,
+    /// HTTP keep-alive in seconds. Zero disables keep-alive.
+    ///
+    /// Rocket 0.5 only uses the value as the HTTP/2 ping interval and has no read timeout (its
+    /// `read_timeout` key is deprecated and ignored), so no read timeout setting is offered.
+    #[serde(default = "default_keep_alive_secs")]
+    pub keep_alive_secs: u32,
+    /// Seconds in-flight requests may keep running after shutdown is requested.
+    #[serde(default = "default_shutdown_grace_secs")]
+    pub shutdown_grace_secs: u32,
+    /// Concurrent playback sessions allowed per non-admin user. Zero disables the limit.
+    #[serde(default = "default_max_playback_sessions_per_user")]
+    pub max_playback_sessions_per_user: u32,
+    /// Seconds a playback session may go without stream requests before it is dropped, so
+    /// sessions abandoned by closed or crashed clients free their slot. Zero keeps sessions until
+    /// they are deleted.
+    #[serde(default = "default_playback_session_idle_secs")]
+    pub playback_session_idle_secs: u64,
+    /// Seconds without active playback sessions before the server shuts itself down. Zero
+    /// disables the idle shutdown.
+    #[serde(default)]
+    pub idle_shutdown_secs: u64,
+    /// Browser origins allowed to call the API cross-origin, or `*` for any. Empty disables CORS.
+    #[serde(default)]
+    pub cors_origins: Vec<String>,
+}
+
+/// Signing algorithm used for API access tokens.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, JsonSchema, PartialEq, Eq, Default)]
+pub enum JwtAlgorithm {
+    /// HMAC with SHA-256 using a process-local shared secret.
+    #[default]
+    #[serde(rename = "HS256")]
+    Hs256,
+    /// RSA with SHA-256 using a PEM private key for signing and public key for verification.
+    #[serde(rename = "RS256")]
+    Rs256,
+}
+
+/// API authentication settings.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+pub struct ApiSettings {
+    /// Algorithm used to sign and verify access tokens.
+    #[serde(default)]
+    pub jwt_algorithm: JwtAlgorithm,
+    /// PEM private key path used to sign RS256 tokens.
+    #[serde(default = "default_jwt_private_key_path")]
+    pub jwt_private_key_path: String,
+    /// PEM public key path used to verify RS256 tokens.
+    #[serde(default = "default_jwt_public_key_path")]
+    pub jwt_public_key_path: String,
+    /// Seconds an access token stays valid after it is issued.
+    #[serde(default = "default_token_ttl_secs")]
+    pub token_ttl_secs: u64,
+    /// Seconds a refresh token stays valid after it is issued.
+    #[serde(default = "default_refresh_token_ttl_secs")]
+    pub refresh_token_ttl_secs: u64,
+    /// Seconds after expiry during which a refresh token is still accepted by `/refresh`.
+    #[serde(default = "default_refresh_grace_secs")]
+    pub refresh_grace_secs: u64,
+    /// Shared secret used to sign HS256 tokens, when a new value is configured. When no secret
+    /// is configured, a random per-process secret is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jwt_secret: Option<String>,
+    /// Earlier HS256 secrets that are still accepted for verification during a rotation, when
+    /// new values are configured.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub jwt_previous_secrets: Vec<String>,
+    /// Stable secret-store reference for the HS256 signing secret.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jwt_secret_ref: Option<String>,
+    /// Stable secret-store reference for the previous HS256 secrets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jwt_previous_secrets_ref: Option<String>,
+    /// BCrypt work factor used when hashing new passwords and PINs, from 4 to 31.
+    #[serde(default = "default_bcrypt_cost")]
+    pub bcrypt_cost: u32,
+    /// Consecutive failed logins after which a username is locked out. Zero disables lockout.
+    #[serde(default = "default_login_lockout_threshold")]
+    pub login_lockout_threshold: u32,
+    /// Seconds a locked-out username must wait before logging in again.
+    #[serde(default = "default_login_lockout_secs")]
+    pub login_lockout_secs: u64,
+    /// Failed logins for one username within the window that mark it as suspicious. Zero
+    /// disables the notification.
+    #[serde(default = "default_suspicious_login_threshold")]
+    pub suspicious_login_threshold: u32,
+    /// Seconds over which failed logins are counted; at most one notification is sent per
+    /// username in each window.
+    #[serde(default = "default_suspicious_login_window_secs")]
+    pub suspicious_login_window_secs: u64,
+    /// Webhook that receives a JSON `suspicious_login` event when the threshold is crossed.
+    #[serde(default)]
+    pub suspicious_login_webhook_url: Option<String>,
+    /// Shortest username accepted when creating or renaming users, in characters.
+    #[serde(default = "default_username_min_length")]
+    pub username_min_length: u32,
+    /// Longest username accepted when creating or renaming users, in characters.
+    #[serde(default = "default_username_max_length")]
+    pub username_max_length: u32,
+    /// Restrict usernames to ASCII letters, digits, `.`, `_`, and `-`.
+    #[serde(default)]
+    pub username_restrict_charset: bool,
 }
 
 /// Application settings.
@@ -692,6 +862,9 @@ pub struct Settings {
     /// Scheduled task settings.
     #[serde(default)]
     pub scheduled_tasks: ScheduledTasksSettings,
+    /// API authentication settings.
+    #[serde(default)]
+    pub api: ApiSettings,
 }
 
 impl Default for GeneralSettings {
@@ -717,6 +890,38 @@ impl Default for ServerSettings {
             cert_path: "cert.pem".into(),
             key_path: "key.pem".into(),
             use_custom_certs: false,
+            keep_alive_secs: default_keep_alive_secs(),
+            shutdown_grace_secs: default_shutdown_grace_secs(),
+            max_playback_sessions_per_user: default_max_playback_sessions_per_user(),
+            playback_session_idle_secs: default_playback_session_idle_secs(),
+            idle_shutdown_secs: 0,
+            cors_origins: Vec::new(),
+        }
+    }
+}
+
+impl Default for ApiSettings {
+    fn default() -> Self {
+        Self {
+            jwt_algorithm: JwtAlgorithm::default(),
+            jwt_private_key_path: default_jwt_private_key_path(),
+            jwt_public_key_path: default_jwt_public_key_path(),
+            token_ttl_secs: default_token_ttl_secs(),
+            refresh_token_ttl_secs: default_refresh_token_ttl_secs(),
+            refresh_grace_secs: default_refresh_grace_secs(),
+            jwt_secret: None,
+            jwt_previous_secrets: Vec::new(),
+            jwt_secret_ref: None,
+            jwt_previous_secrets_ref: None,
+            bcrypt_cost: default_bcrypt_cost(),
+            login_lockout_threshold: default_login_lockout_threshold(),
+            login_lockout_secs: default_login_lockout_secs(),
+            suspicious_login_threshold: default_suspicious_login_threshold(),
+            suspicious_login_window_secs: default_suspicious_login_window_secs(),
+            suspicious_login_webhook_url: None,
+            username_min_length: default_username_min_length(),
+            username_max_length: default_username_max_length(),
+            username_restrict_charset: false,
         }
     }
 }
@@ -842,6 +1047,17 @@ impl Settings {
     }
 }
 
+/// Check settings for combinations that cannot work together.
+pub fn validate_settings(settings: &Settings) -> Result<(), String> {
+    if settings.api.username_min_length > settings.api.username_max_length {
+        return Err(format!(
+            "api.username_min_length ({}) must not be greater than api.username_max_length ({})",
+            settings.api.username_min_length, settings.api.username_max_length
+        ));
+    }
+    Ok(())
+}
+
 /// Normalize settings values before persistence or runtime replacement.
 pub fn normalize_settings(settings: &mut Settings) {
     if let Some(days) = settings.metadata.refresh_interval_days {
@@ -956,6 +1172,43 @@ pub(crate) fn merge_metadata_provider_secret_state(
     }
 }
 
+/// Keep the configured JWT secrets, which the settings API never returns or changes.
+pub(crate) fn merge_api_secret_state(
+    settings: &mut Settings,
+    existing: &Settings,
+) {
+    settings.api.jwt_secret = existing.api.jwt_secret.clone();
+    settings.api.jwt_previous_secrets = existing.api.jwt_previous_secrets.clone();
+    settings.api.jwt_secret_ref = existing.api.jwt_secret_ref.clone();
+    settings.api.jwt_previous_secrets_ref = existing.api.jwt_previous_secrets_ref.clone();
+}
+
+fn api_has_plaintext_jwt_secrets(api: &ApiSettings) -> bool {
+    normalized_secret_value(api.jwt_secret.as_ref()).is_some()
+        || !api.jwt_previous_secrets.is_empty()
+}
+
+fn persist_api_jwt_secrets(api: &mut ApiSettings) -> Result<(), String> {
+    if !api_has_plaintext_jwt_secrets(api) {
+        api.jwt_secret = None;
+        return Ok(());
+    }
+
+    if let Some(secret) = normalized_secret_value(api.jwt_secret.take().as_ref()) {
+        crate::secrets::store_secret(JWT_SECRET_REF, &secret)?;
+        api.jwt_secret_ref = Some(JWT_SECRET_REF.to_string());
+    }
+    let previous_secrets = std::mem::take(&mut api.jwt_previous_secrets);
+    if !previous_secrets.is_empty() {
+        let value = serde_json::to_string(&previous_secrets).map_err(|error| error.to_string())?;
+        crate::secrets::store_secret(JWT_PREVIOUS_SECRETS_REF, &value)?;
+        api.jwt_previous_secrets_ref = Some(JWT_PREVIOUS_SECRETS_REF.to_string());
+    }
+    crate::auth::reset_active_jwt_keys();
+
+    Ok(())
+}
+
 fn persist_metadata_provider_secret(provider: &mut MetadataProviderSettings) -> Result<(), String> {
     if provider.clear_api_key {
         if let Some(secret_ref) = provider.api_key_secret_ref.take() {
@@ -990,6 +1243,7 @@ pub(crate) fn settings_with_persisted_secrets(settings: &Settings) -> Result<Set
     for provider in &mut normalized.metadata.providers {
         persist_metadata_provider_secret(provider)?;
     }
+    persist_api_jwt_secrets(&mut normalized.api)?;
     normalize_settings(&mut normalized);
     Ok(normalized)
 }
@@ -1003,9 +1257,43 @@ pub(crate) fn settings_for_api_response(settings: &Settings) -> Settings {
         provider.api_key_secret_ref = None;
         provider.clear_api_key = false;
     }
+    redacted.api.jwt_secret = None;
+    redacted.api.jwt_previous_secrets.clear();
+    redacted.api.jwt_secret_ref = None;
+    redacted.api.jwt_previous_secrets_ref = None;
     redacted
 }
 
+/// Load the HS256 signing secret and previous secrets, reading them from the secret store when
+/// they are not held in memory.
+pub(crate) fn resolve_api_jwt_secrets(
+    api: &ApiSettings
+) -> Result<(Option<String>, Vec<String>), String> {
+    let secret = match normalized_secret_value(api.jwt_secret.as_ref()) {
+        Some(secret) => Some(secret),
+        None => match normalized_secret_value(api.jwt_secret_ref.as_ref()) {
+            Some(secret_ref) => crate::secrets::load_secret(&secret_ref)?,
+            None => None,
+        },
+    };
+
+    let previous_secrets = if !api.jwt_previous_secrets.is_empty() {
+        api.jwt_previous_secrets.clone()
+    } else {
+        match normalized_secret_value(api.jwt_previous_secrets_ref.as_ref()) {
+            Some(secret_ref) => match crate::secrets::load_secret(&secret_ref)? {
+                Some(value) => serde_json::from_str(&value).map_err(|error| {
+                    format!("Failed to parse stored previous JWT secrets: {error}")
+                })?,
+                None => Vec::new(),
+            },
+            None => Vec::new(),
+        }
+    };
+
+    Ok((secret, previous_secrets))
+}
+
 pub(crate) fn resolve_metadata_provider_api_key(
     provider: &mut MetadataProviderSettings
 ) -> Result<(), String> {
@@ -1181,6 +1469,7 @@ pub fn save_database_settings(
     conn: &mut diesel::SqliteConnection,
     settings: &Settings,
 ) -> Result<(), String> {
+    validate_settings(settings)?;
     let normalized = settings_with_persisted_secrets(settings)?;
 
     upsert_runtime_setting(
@@ -1208,6 +1497,11 @@ pub fn save_database_settings(
         SCHEDULED_TASKS_SETTINGS_KEY,
         runtime_setting_value(&normalized.scheduled_tasks)?,
     )?;
+    upsert_runtime_setting(
+        conn,
+        API_SETTINGS_KEY,
+        runtime_setting_value(&normalized.api)?,
+    )?;
     Ok(())
 }
 
@@ -1243,6 +1537,11 @@ pub fn seed_database_settings(
         SCHEDULED_TASKS_SETTINGS_KEY,
         runtime_setting_value(&normalized.scheduled_tasks)?,
     )?;
+    insert_runtime_setting_if_missing(
+        conn,
+        API_SETTINGS_KEY,
+        runtime_setting_value(&normalized.api)?,
+    )?;
     Ok(())
 }
 
@@ -1258,6 +1557,7 @@ pub fn load_database_settings(
             SERVER_SETTINGS_KEY,
             FFMPEG_SETTINGS_KEY,
             SCHEDULED_TASKS_SETTINGS_KEY,
+            API_SETTINGS_KEY,
         ]))
         .select(AppSetting::as_select())
         .load::<AppSetting>(conn)
@@ -1284,16 +1584,25 @@ pub fn load_database_settings(
                 settings.scheduled_tasks =
                     parse_runtime_setting(&row.value, SCHEDULED_TASKS_SETTINGS_KEY)?;
             }
+            API_SETTINGS_KEY => {
+                settings.api = parse_runtime_setting(&row.value, API_SETTINGS_KEY)?;
+            }
             _ => {}
         }
     }
-    let has_plaintext_provider_secrets = settings
+    let has_plaintext_secrets = settings
         .metadata
         .providers
         .iter()
-        .any(|provider| normalized_secret_value(provider.api_key.as_ref()).is_some());
+        .any(|provider| normalized_secret_value(provider.api_key.as_ref()).is_some())
+        || api_has_plaintext_jwt_secrets(&settings.api);
+    if let Err(error) = validate_settings(&settings) {
+        log::warn!("{}; using the default username length limits", error);
+        settings.api.username_min_length = default_username_min_length();
+        settings.api.username_max_length = default_username_max_length();
+    }
     normalize_settings(&mut settings);
-    if has_plaintext_provider_secrets {
+    if has_plaintext_secrets {
         settings = settings_with_persisted_secrets(&settings)?;
         save_database_settings(conn, &settings)?;
     }
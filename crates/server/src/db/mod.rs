@@ -27,8 +27,14 @@ use diesel_migrations::{
     MigrationHarness,
     embed_migrations,
 };
+use rocket::request::{
+    FromRequest,
+    Outcome,
+    Request,
+};
 use rocket::{
     Build,
+    Orbit,
     Rocket,
     fairing::{
         Fairing,
@@ -65,6 +71,32 @@ impl fmt::Display for MigrationOrderError {
 
 impl Error for MigrationOrderError {}
 
+/// A single SQLite migration failed while it was being applied.
+#[derive(Debug)]
+struct MigrationFailedError {
+    migration: String,
+    source: Box<dyn Error + Send + Sync>,
+}
+
+impl fmt::Display for MigrationFailedError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "SQLite migration {} failed: {}",
+            self.migration, self.source
+        )
+    }
+}
+
+impl Error for MigrationFailedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.source)
+    }
+}
+
 /// Apply SQLite pragmas that improve concurrency and reduce lock contention.
 pub fn configure_sqlite_connection(
     conn: &mut diesel::SqliteConnection
@@ -76,6 +108,9 @@ pub fn configure_sqlite_connection(
 }
 
 /// Run pending SQLite migrations in the order declared by `SQLITE_MIGRATION_ORDER`.
+///
+/// A failing migration is reported with its full name so startup errors point at the exact
+/// revision that could not be applied.
 pub fn run_pending_sqlite_migrations(
     conn: &mut diesel::SqliteConnection
 ) -> MigrationResult<Vec<MigrationVersion<'static>>> {
@@ -94,7 +129,13 @@ pub fn run_pending_sqlite_migrations(
                 "SQLite migration revision {version} is listed but not embedded"
             ));
         };
-        applied.push(conn.run_migration(&*migration)?);
+        let applied_version = conn.run_migration(&*migration).map_err(|source| {
+            Box::new(MigrationFailedError {
+                migration: migration.name().to_string(),
+                source,
+            })
+        })?;
+        applied.push(applied_version);
     }
 
     Ok(applied)
@@ -266,6 +307,28 @@ fn release_sqlite_database_lock(conn: &mut diesel::SqliteConnection) {
 #[database("sqlite_db")]
 pub struct DbConn(diesel::SqliteConnection);
 
+/// Request guard that checks out a [`DbConn`] only when the handler asks for one.
+///
+/// Use it in handlers that do slow work, such as password hashing, before touching the
+/// database, so that work does not hold a pooled connection.
+pub struct LazyDbConn<'r>(&'r Rocket<Orbit>);
+
+impl LazyDbConn<'_> {
+    /// Check out a pooled connection, or `None` if the pool timed out.
+    pub async fn get(&self) -> Option<DbConn> {
+        DbConn::get_one(self.0).await
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for LazyDbConn<'r> {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(LazyDbConn(request.rocket()))
+    }
+}
+
 /// Fairing to run migrations when the application starts.
 pub struct Migrate;
 
@@ -285,15 +348,28 @@ impl Fairing for Migrate {
         &self,
         rocket: Rocket<Build>,
     ) -> Result<Rocket<Build>, Rocket<Build>> {
-        if let Some(conn) = DbConn::get_one(&rocket).await {
-            let _ = conn
-                .run(|c| {
-                    configure_sqlite_connection(c).expect("Failed to configure SQLite connection");
-                    run_pending_sqlite_migrations(c).expect("Failed to run migrations");
-                })
-                .await;
+        let Some(conn) = DbConn::get_one(&rocket).await else {
+            log::error!("Database migrations could not run: no SQLite connection is available");
+            return Err(rocket);
+        };
+
+        let result = conn
+            .run(|c| {
+                configure_sqlite_connection(c)
+                    .map_err(|error| format!("Failed to configure SQLite connection: {error}"))?;
+                run_pending_sqlite_migrations(c)
+                    .map_err(|error| format!("Failed to run SQLite migrations: {error}"))
+            })
+            .await;
+
+        match result {
+            Ok(_) => Ok(rocket),
+            Err(error) => {
+                // Serving requests against a partially migrated schema is worse than not starting.
+                log::error!("{}; refusing to start the web server", error);
+                Err(rocket)
+            }
         }
-        Ok(rocket)
     }
 }
 
@@ -325,3 +401,13 @@ impl rocket_okapi::request::OpenApiFromRequest<'_> for DbConn {
         Ok(rocket_okapi::request::RequestHeaderInput::None)
     }
 }
+
+impl<'r> rocket_okapi::request::OpenApiFromRequest<'r> for LazyDbConn<'r> {
+    fn from_request_input(
+        _gen: &mut rocket_okapi::gen::OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> rocket_okapi::Result<rocket_okapi::request::RequestHeaderInput> {
+        Ok(rocket_okapi::request::RequestHeaderInput::None)
+    }
+}
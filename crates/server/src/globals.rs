@@ -1,10 +1,17 @@
 //! Miscellaneous utilities for the application.
 
+// standard imports
+use std::fmt;
+use std::net::Ipv6Addr;
+
 // lib imports
 use once_cell::sync::Lazy;
 
 // local imports
-use crate::config::current_settings;
+use crate::config::{
+    ServerSettings,
+    current_settings,
+};
 
 // global constants and variables
 pub(crate) static GLOBAL_APP_NAME: &str = "Koko";
@@ -63,14 +70,82 @@ impl AppPaths {
     }
 }
 
+/// Validated base URL for reaching the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerUrl {
+    scheme: &'static str,
+    host: String,
+    port: u16,
+}
+
+impl ServerUrl {
+    /// Build a server URL, rejecting empty or malformed hosts and port 0.
+    pub fn new(
+        use_https: bool,
+        host: &str,
+        port: u16,
+    ) -> Result<Self, String> {
+        let host = host.trim();
+        let host = host
+            .strip_prefix('[')
+            .and_then(|inner| inner.strip_suffix(']'))
+            .unwrap_or(host);
+        if host.is_empty() {
+            return Err("Server host must not be empty".to_string());
+        }
+        if host
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '/' | '?' | '#' | '@' | '[' | ']'))
+        {
+            return Err(format!("Server host {host:?} contains invalid characters"));
+        }
+        if host.contains(':') && host.parse::<Ipv6Addr>().is_err() {
+            return Err(format!("Server host {host:?} is not a valid IPv6 address"));
+        }
+        if port == 0 {
+            return Err("Server port must be between 1 and 65535".to_string());
+        }
+
+        Ok(Self {
+            scheme: if use_https { "https" } else { "http" },
+            host: host.to_string(),
+            port,
+        })
+    }
+
+    /// Build a server URL from the server settings.
+    pub fn from_settings(settings: &ServerSettings) -> Result<Self, String> {
+        Self::new(settings.use_https, &settings.address, settings.port)
+    }
+}
+
+impl fmt::Display for ServerUrl {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        if self.host.contains(':') {
+            write!(f, "{}://[{}]:{}", self.scheme, self.host, self.port)
+        } else {
+            write!(f, "{}://{}:{}", self.scheme, self.host, self.port)
+        }
+    }
+}
+
 /// Get the server URL based on the global settings.
 pub fn get_server_url() -> String {
     let settings = current_settings();
-    let schema = if settings.server.use_https { "https" } else { "http" };
-    format!(
-        "{}://{}:{}",
-        schema, settings.server.address, settings.server.port
-    )
+    match ServerUrl::from_settings(&settings.server) {
+        Ok(url) => url.to_string(),
+        Err(error) => {
+            log::warn!("Invalid server URL settings: {}", error);
+            let schema = if settings.server.use_https { "https" } else { "http" };
+            format!(
+                "{}://{}:{}",
+                schema, settings.server.address, settings.server.port
+            )
+        }
+    }
 }
 
 /// Global AppPaths instance.
@@ -13,6 +13,7 @@ pub mod globals;
 mod logging;
 pub mod media;
 pub mod metadata;
+pub mod metrics;
 pub mod scanner;
 pub mod scheduled_tasks;
 mod secrets;
@@ -29,15 +30,37 @@ pub mod web;
 pub fn main() {
     logging::init().expect("Failed to initialize logging");
 
+    // Build the web server first so the shutdown timeout reflects the DB-backed server settings
+    let rocket = web::rocket();
+    let shutdown_timeout = web::shutdown_timeout(&crate::config::current_settings().server);
+
     // Create a shutdown coordinator to manage all threads
-    let mut coordinator = signal_handler::ShutdownCoordinator::new();
+    let mut coordinator = signal_handler::ShutdownCoordinator::with_timeout(shutdown_timeout);
 
-    // Register the web server thread
-    coordinator.register_async_thread("web-server", |shutdown_signal| async move {
-        web::launch_with_shutdown(shutdown_signal).await;
+    // Register the web server thread; a startup failure triggers the coordinated shutdown
+    let web_server_failed = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let web_server_failed_flag = web_server_failed.clone();
+    coordinator.register_async_thread("web-server", move |shutdown_signal| async move {
+        if let Err(error) = web::launch_rocket_with_shutdown(rocket, shutdown_signal).await {
+            log::error!("Web server failed: {}", error);
+            web_server_failed_flag.store(true, std::sync::atomic::Ordering::SeqCst);
+        }
         log::info!("Web server thread completed");
     });
 
+    // Register the idle watchdog when an idle shutdown is configured
+    let idle_timeout = idle_shutdown_timeout();
+    if !idle_timeout.is_zero() {
+        coordinator.register_thread("idle-watchdog", move |shutdown_signal| {
+            signal_handler::run_idle_watchdog(
+                &shutdown_signal,
+                idle_timeout,
+                IDLE_WATCHDOG_POLL_INTERVAL,
+                web::active_client_count,
+            );
+        });
+    }
+
     // Start the monitoring system
     coordinator.start_monitor();
 
@@ -51,9 +74,18 @@ pub fn main() {
     coordinator.shutdown();
 
     // Wait for all threads to complete
-    coordinator.wait_for_completion();
+    let report = coordinator.wait_for_completion();
 
-    log::info!("Application shutdown complete");
+    log::info!(
+        "Application shutdown complete: {}/{} threads succeeded in {:?}",
+        report.succeeded,
+        report.total,
+        report.duration
+    );
+
+    if web_server_failed.load(std::sync::atomic::Ordering::SeqCst) {
+        std::process::exit(1);
+    }
 }
 
 /// Main entry point for the application without tray support.
@@ -63,9 +95,41 @@ pub fn main() {
     logging::init().expect("Failed to initialize logging");
     log::info!("Starting without tray support");
 
+    // Build the web server first so the idle timeout reflects the DB-backed server settings
+    let rocket = web::rocket();
+    let shutdown_signal = signal_handler::ShutdownSignal::new();
+
+    let idle_timeout = idle_shutdown_timeout();
+    if !idle_timeout.is_zero() {
+        let watchdog_signal = shutdown_signal.clone();
+        std::thread::Builder::new()
+            .name("idle-watchdog".to_string())
+            .spawn(move || {
+                signal_handler::run_idle_watchdog(
+                    &watchdog_signal,
+                    idle_timeout,
+                    IDLE_WATCHDOG_POLL_INTERVAL,
+                    web::active_client_count,
+                );
+            })
+            .expect("Failed to spawn idle-watchdog thread");
+    }
+
     let runtime =
         tokio::runtime::Runtime::new().expect("Failed to create tokio runtime for web server");
-    runtime.block_on(web::launch_with_shutdown(
-        signal_handler::ShutdownSignal::new(),
-    ));
+    if let Err(error) = runtime.block_on(web::launch_rocket_with_shutdown(rocket, shutdown_signal))
+    {
+        log::error!("Web server failed: {}", error);
+        std::process::exit(1);
+    }
+}
+
+/// How often the idle watchdog checks for active clients.
+#[cfg(not(tarpaulin_include))]
+const IDLE_WATCHDOG_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Configured idle period before the server shuts itself down; zero when disabled.
+#[cfg(not(tarpaulin_include))]
+fn idle_shutdown_timeout() -> std::time::Duration {
+    std::time::Duration::from_secs(crate::config::current_settings().server.idle_shutdown_secs)
 }
@@ -652,10 +652,42 @@ pub struct PlaybackSession {
     pub decision: PlaybackDecision,
     /// Unix timestamp when the session was created.
     pub created_at: i64,
+    /// Unix timestamp of the session's most recent stream request.
+    pub last_active_at: i64,
     /// Selected zero-based audio stream index among audio streams.
     pub audio_stream_index: Option<usize>,
 }
 
+/// Whether a playback session has had no stream request within `idle_secs` of `now`. An
+/// `idle_secs` of zero never treats a session as idle.
+pub fn playback_session_is_idle(
+    session: &PlaybackSession,
+    now: i64,
+    idle_secs: u64,
+) -> bool {
+    idle_secs != 0
+        && now.saturating_sub(session.last_active_at)
+            >= i64::try_from(idle_secs).unwrap_or(i64::MAX)
+}
+
+/// Remove playback sessions with no stream request within `idle_secs` of `now`, returning the
+/// removed session IDs. An `idle_secs` of zero keeps every session.
+pub fn prune_idle_playback_sessions(
+    sessions: &mut HashMap<String, PlaybackSession>,
+    now: i64,
+    idle_secs: u64,
+) -> Vec<String> {
+    let idle_ids = sessions
+        .iter()
+        .filter(|(_, session)| playback_session_is_idle(session, now, idle_secs))
+        .map(|(session_id, _)| session_id.clone())
+        .collect::<Vec<_>>();
+    for session_id in &idle_ids {
+        sessions.remove(session_id);
+    }
+    idle_ids
+}
+
 /// One unmatched media item that is eligible for automatic metadata linking.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AutomaticMetadataCandidate {
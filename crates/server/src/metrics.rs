@@ -0,0 +1,71 @@
+//! Process-wide counters exposed in Prometheus text format.
+
+// standard imports
+use std::fmt::Write;
+use std::sync::atomic::{
+    AtomicU64,
+    Ordering,
+};
+
+static LOGINS_TOTAL: AtomicU64 = AtomicU64::new(0);
+static FAILED_LOGINS_TOTAL: AtomicU64 = AtomicU64::new(0);
+static STREAM_REQUESTS_TOTAL: AtomicU64 = AtomicU64::new(0);
+static ACTIVE_PLAYBACK_SESSIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Count a successful password or PIN login.
+pub fn record_login() {
+    LOGINS_TOTAL.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count a rejected password or PIN login.
+pub fn record_failed_login() {
+    FAILED_LOGINS_TOTAL.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count a media stream request that is about to be served.
+pub fn record_stream_request() {
+    STREAM_REQUESTS_TOTAL.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Record the current number of playback sessions.
+pub fn set_active_playback_sessions(count: usize) {
+    ACTIVE_PLAYBACK_SESSIONS.store(count as u64, Ordering::Relaxed);
+}
+
+/// Render all metrics in the Prometheus text exposition format.
+pub fn render() -> String {
+    let metrics = [
+        (
+            "koko_logins_total",
+            "counter",
+            "Successful password and PIN logins.",
+            &LOGINS_TOTAL,
+        ),
+        (
+            "koko_failed_logins_total",
+            "counter",
+            "Rejected password and PIN logins.",
+            &FAILED_LOGINS_TOTAL,
+        ),
+        (
+            "koko_stream_requests_total",
+            "counter",
+            "Media stream requests served.",
+            &STREAM_REQUESTS_TOTAL,
+        ),
+        (
+            "koko_active_playback_sessions",
+            "gauge",
+            "Playback sessions currently open.",
+            &ACTIVE_PLAYBACK_SESSIONS,
+        ),
+    ];
+
+    let mut output = String::new();
+    for (name, kind, help, value) in metrics {
+        let _ = writeln!(output, "# HELP {name} {help}");
+        let _ = writeln!(output, "# TYPE {name} {kind}");
+        let _ = writeln!(output, "{name} {}", value.load(Ordering::Relaxed));
+    }
+    output
+}
@@ -1,18 +1,84 @@
 //! Signal handling utilities for graceful shutdown.
 
-use std::sync::Arc;
 use std::sync::atomic::{
     AtomicBool,
+    AtomicU8,
     Ordering,
 };
+use std::sync::{
+    Arc,
+    Condvar,
+    Mutex,
+    PoisonError,
+};
 use std::thread::JoinHandle;
-use std::time::Duration;
+use std::time::{
+    Duration,
+    Instant,
+};
+
+/// Why shutdown was requested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownReason {
+    /// Ordinary shutdown, such as a signal, tray exit, or a finished thread.
+    Requested,
+    /// An administrator asked for a restart; the service manager is expected to start the
+    /// process again.
+    Restart,
+    /// No clients were active for the configured idle period.
+    IdleTimeout,
+}
+
+impl ShutdownReason {
+    fn as_u8(self) -> u8 {
+        match self {
+            ShutdownReason::Requested => 1,
+            ShutdownReason::Restart => 2,
+            ShutdownReason::IdleTimeout => 3,
+        }
+    }
+
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(ShutdownReason::Requested),
+            2 => Some(ShutdownReason::Restart),
+            3 => Some(ShutdownReason::IdleTimeout),
+            _ => None,
+        }
+    }
+}
+
+/// Outcome of waiting for all coordinated threads to finish.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// Number of threads that were joined.
+    pub total: usize,
+    /// Number of threads that completed without panicking.
+    pub succeeded: usize,
+    /// Names of threads that panicked.
+    pub failed: Vec<String>,
+    /// Time spent waiting for the threads to finish.
+    pub duration: Duration,
+}
+
+impl ShutdownReport {
+    /// Whether every thread completed without panicking.
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
 
 /// A thread-safe shutdown signal that can be shared across threads.
 #[derive(Clone)]
 pub struct ShutdownSignal {
     /// Atomic boolean indicating whether shutdown has been requested.
     shutdown: Arc<AtomicBool>,
+    /// Reason recorded by the first shutdown request, or zero when none was made.
+    reason: Arc<AtomicU8>,
+    /// Wakes threads blocked in [`ShutdownSignal::wait`].
+    waiters: Arc<(Mutex<()>, Condvar)>,
+    /// Wakes tasks awaiting [`ShutdownSignal::wait_async`].
+    notify: Arc<tokio::sync::Notify>,
 }
 
 impl ShutdownSignal {
@@ -20,23 +86,66 @@ impl ShutdownSignal {
     pub fn new() -> Self {
         Self {
             shutdown: Arc::new(AtomicBool::new(false)),
+            reason: Arc::new(AtomicU8::new(0)),
+            waiters: Arc::new((Mutex::new(()), Condvar::new())),
+            notify: Arc::new(tokio::sync::Notify::new()),
         }
     }
 
     /// Signal that shutdown has been requested.
     pub fn shutdown(&self) {
-        self.shutdown.store(true, Ordering::Relaxed);
+        self.shutdown_with_reason(ShutdownReason::Requested);
+    }
+
+    /// Signal that shutdown has been requested for a specific reason.
+    ///
+    /// Only the first reason is kept, so a later ordinary shutdown does not hide a restart.
+    pub fn shutdown_with_reason(
+        &self,
+        reason: ShutdownReason,
+    ) {
+        let _ = self
+            .reason
+            .compare_exchange(0, reason.as_u8(), Ordering::SeqCst, Ordering::SeqCst);
+        self.shutdown.store(true, Ordering::SeqCst);
+
+        // Take the lock so a waiter cannot miss the notification between its check and its wait.
+        let (lock, condvar) = &*self.waiters;
+        let _guard = lock.lock().unwrap_or_else(PoisonError::into_inner);
+        condvar.notify_all();
+        self.notify.notify_waiters();
     }
 
     /// Check if shutdown has been requested.
     pub fn is_shutdown(&self) -> bool {
-        self.shutdown.load(Ordering::Relaxed)
+        self.shutdown.load(Ordering::SeqCst)
+    }
+
+    /// Get the reason for the shutdown, if one has been requested.
+    pub fn reason(&self) -> Option<ShutdownReason> {
+        ShutdownReason::from_u8(self.reason.load(Ordering::SeqCst))
+    }
+
+    /// Wait asynchronously until shutdown is requested, returning immediately if it already was.
+    pub async fn wait_async(&self) {
+        loop {
+            // Register interest before checking the flag so a shutdown in between is not missed.
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if self.is_shutdown() {
+                return;
+            }
+            notified.await;
+        }
     }
 
-    /// Wait for shutdown signal.
+    /// Block until shutdown is requested, returning immediately if it already was.
     pub fn wait(&self) {
+        let (lock, condvar) = &*self.waiters;
+        let mut guard = lock.lock().unwrap_or_else(PoisonError::into_inner);
         while !self.is_shutdown() {
-            std::thread::sleep(Duration::from_millis(100));
+            guard = condvar.wait(guard).unwrap_or_else(PoisonError::into_inner);
         }
     }
 }
@@ -47,6 +156,35 @@ impl Default for ShutdownSignal {
     }
 }
 
+/// Shut down with [`ShutdownReason::IdleTimeout`] once `active_clients` has reported zero for
+/// `idle_timeout`.
+///
+/// The client count is polled every `poll_interval`, and the watchdog returns early if `signal`
+/// is shut down for another reason.
+pub fn run_idle_watchdog<F>(
+    signal: &ShutdownSignal,
+    idle_timeout: Duration,
+    poll_interval: Duration,
+    active_clients: F,
+) where
+    F: Fn() -> usize,
+{
+    let mut idle_since = Some(Instant::now());
+    while !signal.is_shutdown() {
+        if active_clients() > 0 {
+            idle_since = None;
+        } else if idle_since.get_or_insert_with(Instant::now).elapsed() >= idle_timeout {
+            log::info!(
+                "No clients were active for {:?}, shutting down",
+                idle_timeout
+            );
+            signal.shutdown_with_reason(ShutdownReason::IdleTimeout);
+            return;
+        }
+        std::thread::sleep(poll_interval);
+    }
+}
+
 /// Represents a managed thread that can be gracefully shut down.
 pub struct ManagedThread {
     name: String,
@@ -161,6 +299,72 @@ impl ShutdownCoordinator {
         shutdown_signal
     }
 
+    /// Register a thread that is started again from `thread_fn` if it panics.
+    ///
+    /// Each panic restarts the thread on a fresh OS thread, up to `max_restarts` times. Once the
+    /// limit is exceeded the global shutdown is triggered and the thread is reported as failed.
+    /// A panic after shutdown has been requested is not retried.
+    pub fn register_restartable_thread<F>(
+        &mut self,
+        name: &str,
+        max_restarts: u32,
+        thread_fn: F,
+    ) -> ShutdownSignal
+    where
+        F: Fn(ShutdownSignal) + Send + Sync + 'static,
+    {
+        let shutdown_signal = ShutdownSignal::new();
+        let signal_clone = shutdown_signal.clone();
+        let main_signal = self.main_signal.clone();
+        let thread_fn = Arc::new(thread_fn);
+        let name_owned = name.to_string();
+
+        let handle = std::thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || {
+                let mut restarts = 0;
+                loop {
+                    let attempt_fn = Arc::clone(&thread_fn);
+                    let attempt_signal = signal_clone.clone();
+                    let attempt = std::thread::Builder::new()
+                        .name(name_owned.clone())
+                        .spawn(move || attempt_fn(attempt_signal))
+                        .unwrap_or_else(|_| panic!("Failed to spawn {} thread", name_owned))
+                        .join();
+
+                    let Err(payload) = attempt else {
+                        break;
+                    };
+                    if signal_clone.is_shutdown() || main_signal.is_shutdown() {
+                        std::panic::resume_unwind(payload);
+                    }
+                    if restarts >= max_restarts {
+                        log::error!(
+                            "{} thread panicked after {} restarts, initiating global shutdown",
+                            name_owned,
+                            restarts
+                        );
+                        main_signal.shutdown();
+                        std::panic::resume_unwind(payload);
+                    }
+
+                    restarts += 1;
+                    log::warn!(
+                        "{} thread panicked, restarting ({}/{})",
+                        name_owned,
+                        restarts,
+                        max_restarts
+                    );
+                }
+            })
+            .unwrap_or_else(|_| panic!("Failed to spawn {} thread", name));
+
+        let managed_thread = ManagedThread::new(name.to_string(), handle, shutdown_signal.clone());
+        self.threads.push(managed_thread);
+
+        shutdown_signal
+    }
+
     /// Register an async thread for shutdown coordination.
     pub fn register_async_thread<F, Fut>(
         &mut self,
@@ -224,7 +428,9 @@ impl ShutdownCoordinator {
                             "Monitor detected {} thread completed, initiating global shutdown",
                             name
                         );
-                        main_signal.shutdown();
+                        main_signal.shutdown_with_reason(
+                            signal.reason().unwrap_or(ShutdownReason::Requested),
+                        );
                         break;
                     }
                 }
@@ -276,10 +482,12 @@ impl ShutdownCoordinator {
         self.main_signal.shutdown();
     }
 
-    /// Wait for all threads to complete.
-    pub fn wait_for_completion(self) {
+    /// Wait for all threads to complete and report which ones failed.
+    pub fn wait_for_completion(self) -> ShutdownReport {
         log::info!("Waiting for all threads to complete");
 
+        let started_at = Instant::now();
+        let total = self.threads.len();
         let mut failed_threads = Vec::new();
         for thread in self.threads {
             let thread_name = thread.name().to_string();
@@ -297,6 +505,13 @@ impl ShutdownCoordinator {
         } else {
             log::warn!("Some threads failed: {:?}", failed_threads);
         }
+
+        ShutdownReport {
+            total,
+            succeeded: total - failed_threads.len(),
+            failed: failed_threads,
+            duration: started_at.elapsed(),
+        }
     }
 
     /// Get the number of registered threads.
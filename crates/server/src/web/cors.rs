@@ -0,0 +1,104 @@
+//! Cross-origin resource sharing for browser clients hosted on another origin.
+
+// lib imports
+use rocket::fairing::{
+    Fairing,
+    Info,
+    Kind,
+};
+use rocket::http::{
+    Header,
+    Method,
+    Status,
+};
+use rocket::{
+    Request,
+    Response,
+};
+
+/// Methods browsers may use for cross-origin API requests.
+const ALLOWED_METHODS: &str = "GET, POST, PUT, PATCH, DELETE, OPTIONS";
+
+/// Request headers browsers may send with cross-origin API requests.
+const ALLOWED_HEADERS: &str = "Authorization, Content-Type";
+
+/// Seconds browsers may cache a preflight response.
+const PREFLIGHT_MAX_AGE_SECS: u32 = 600;
+
+/// Fairing that adds CORS headers for configured origins and answers preflight requests.
+///
+/// An empty origin list disables CORS, and `*` allows any origin.
+pub(crate) struct Cors {
+    origins: Vec<String>,
+}
+
+impl Cors {
+    /// Create a CORS fairing for the given allowed origins.
+    pub(crate) fn new(origins: &[String]) -> Self {
+        Self {
+            origins: origins
+                .iter()
+                .map(|origin| origin.trim().trim_end_matches('/').to_string())
+                .filter(|origin| !origin.is_empty())
+                .collect(),
+        }
+    }
+
+    fn allows(
+        &self,
+        origin: &str,
+    ) -> bool {
+        self.origins
+            .iter()
+            .any(|allowed| allowed == "*" || allowed.eq_ignore_ascii_case(origin))
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for Cors {
+    fn info(&self) -> Info {
+        Info {
+            name: "CORS",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(
+        &self,
+        request: &'r Request<'_>,
+        response: &mut Response<'r>,
+    ) {
+        if self.origins.is_empty() {
+            return;
+        }
+        // Every response depends on the request origin once CORS is on, including those without
+        // CORS headers, so shared caches must not reuse one across origins.
+        response.adjoin_header(Header::new("Vary", "Origin"));
+
+        let Some(origin) = request.headers().get_one("Origin") else {
+            return;
+        };
+        if !self.allows(origin) {
+            return;
+        }
+
+        response.set_header(Header::new(
+            "Access-Control-Allow-Origin",
+            origin.to_string(),
+        ));
+
+        let is_preflight = request.method() == Method::Options
+            && request.headers().contains("Access-Control-Request-Method");
+        if is_preflight {
+            response.set_status(Status::NoContent);
+            response.set_sized_body(0, std::io::Cursor::new(""));
+            response.remove_header("Content-Type");
+            response.set_header(Header::new("Access-Control-Allow-Methods", ALLOWED_METHODS));
+            response.set_header(Header::new("Access-Control-Allow-Headers", ALLOWED_HEADERS));
+            response.set_header(Header::new(
+                "Access-Control-Max-Age",
+                PREFLIGHT_MAX_AGE_SECS.to_string(),
+            ));
+        }
+    }
+}
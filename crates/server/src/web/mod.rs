@@ -1,8 +1,13 @@
 //! Web server utilities for the application.
 
 // modules
+mod cors;
 pub(crate) mod routes;
 
+// standard imports
+use std::net::IpAddr;
+use std::time::Duration;
+
 // lib imports
 use diesel::Connection;
 use rocket::config::Config;
@@ -16,8 +21,14 @@ use rocket_okapi::{
 };
 
 // local imports
+use crate::auth::{
+    AdminStatusCache,
+    LoginThrottle,
+    SuspiciousLoginMonitor,
+};
 use crate::certs;
 use crate::config::{
+    ServerSettings,
     current_settings,
     load_database_settings,
     replace_current_settings,
@@ -30,7 +41,25 @@ use crate::db::{
     initialize_sqlite_database,
 };
 use crate::globals;
-use crate::signal_handler::ShutdownSignal;
+use crate::media::{
+    BinaryCapability,
+    TranscodingCapability,
+    inspect_transcoding_capability,
+};
+use crate::signal_handler::{
+    ShutdownReason,
+    ShutdownSignal,
+};
+
+/// Seconds Rocket waits for connections to close once the shutdown grace period has elapsed.
+pub const SHUTDOWN_MERCY_SECS: u32 = 3;
+
+/// Longest time the web server may take to stop after shutdown is requested.
+pub fn shutdown_timeout(settings: &ServerSettings) -> Duration {
+    Duration::from_secs(u64::from(
+        settings.shutdown_grace_secs + SHUTDOWN_MERCY_SECS,
+    ))
+}
 
 /// Build the web server.
 pub fn rocket() -> rocket::Rocket<rocket::Build> {
@@ -72,14 +101,7 @@ pub fn rocket_with_db_path(custom_db_path: Option<String>) -> rocket::Rocket<roc
     };
 
     // the cert path changes depending on if the user wants to use custom certs
-    let (cert_path, key_path);
-    if !settings.server.use_custom_certs {
-        cert_path = format!("{}/cert.pem", settings.general.data_dir);
-        key_path = format!("{}/key.pem", settings.general.data_dir);
-    } else {
-        cert_path = settings.server.cert_path.clone();
-        key_path = settings.server.key_path.clone();
-    }
+    let (cert_path, key_path) = certs::certificate_paths(&settings);
 
     if settings.server.use_https {
         certs::ensure_certificates_exist(cert_path.clone(), key_path.clone());
@@ -98,6 +120,9 @@ pub fn rocket_with_db_path(custom_db_path: Option<String>) -> rocket::Rocket<roc
         ))
         .merge(("address", settings.server.address.clone()))
         .merge(("port", settings.server.port))
+        .merge(("keep_alive", settings.server.keep_alive_secs))
+        .merge(("shutdown.grace", settings.server.shutdown_grace_secs))
+        .merge(("shutdown.mercy", SHUTDOWN_MERCY_SECS))
         .merge((
             "tls",
             if settings.server.use_https {
@@ -107,10 +132,20 @@ pub fn rocket_with_db_path(custom_db_path: Option<String>) -> rocket::Rocket<roc
             },
         ));
 
+    let (api_routes, api_spec) = routes::api_routes_and_spec();
+
     rocket::custom(figment)
+        .manage(AdminStatusCache::default())
+        .manage(LoginThrottle::default())
+        .manage(SuspiciousLoginMonitor::default())
+        .manage(routes::admin::PendingRestart::default())
+        .manage(routes::admin::MaintenanceMode::default())
+        .manage(ShutdownSignal::new())
+        .manage(routes::common::ApiSpec(api_spec))
         .attach(DbConn::fairing())
         .attach(Migrate)
         .attach(ReleaseDatabase)
+        .attach(cors::Cors::new(&settings.server.cors_origins))
         .attach(AdHoc::on_liftoff("Start background workers", |rocket| {
             Box::pin(async move {
                 let scheduled_tasks_db = DbConn::get_one(rocket).await;
@@ -145,7 +180,8 @@ pub fn rocket_with_db_path(custom_db_path: Option<String>) -> rocket::Rocket<roc
                 }
             })
         }))
-        .mount("/", routes::api_routes())
+        .mount("/", api_routes)
+        .register("/", routes::api_catchers())
         .mount(
             "/swagger-ui/",
             make_swagger_ui(&SwaggerUIConfig {
@@ -191,17 +227,113 @@ fn sqlite_database_url(db_path: &str) -> String {
     }
 }
 
+/// Number of clients currently using the server, measured by playback sessions that are still
+/// streaming.
+///
+/// Sessions left idle for `server.playback_session_idle_secs` are not counted, so an abandoned
+/// session cannot hold off the idle shutdown. A session registry that is busy with a request
+/// counts as one active client.
+pub fn active_client_count() -> usize {
+    routes::media::active_playback_session_count().unwrap_or(1)
+}
+
+/// Collect the sorted, distinct `vN` API versions from mounted route paths such as `/api/v1/...`.
+pub fn mounted_api_versions<'a>(paths: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut versions: Vec<String> = paths
+        .into_iter()
+        .filter_map(|path| path.strip_prefix("/api/")?.split('/').next())
+        .filter(|segment| {
+            segment.strip_prefix('v').is_some_and(|number| {
+                !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit())
+            })
+        })
+        .map(str::to_string)
+        .collect();
+    versions.sort();
+    versions.dedup();
+    versions
+}
+
+/// Format the summary logged once the web server has ignited.
+pub fn startup_summary(
+    address: IpAddr,
+    port: u16,
+    tls: bool,
+    database_url: &str,
+    api_versions: &[String],
+    transcoding: &TranscodingCapability,
+) -> String {
+    fn describe_binary(capability: &BinaryCapability) -> String {
+        if capability.available {
+            let version = capability.version.as_deref().unwrap_or("unknown version");
+            format!("available ({version})")
+        } else {
+            let error = capability.error.as_deref().unwrap_or("not found");
+            format!("unavailable ({}: {error})", capability.configured_path)
+        }
+    }
+
+    [
+        format!(
+            "{} {} startup summary",
+            globals::GLOBAL_APP_NAME,
+            env!("CARGO_PKG_VERSION")
+        ),
+        format!("  listen: {}", std::net::SocketAddr::new(address, port)),
+        format!("  tls: {}", if tls { "enabled" } else { "disabled" }),
+        format!("  database: {database_url}"),
+        format!(
+            "  api versions: {}",
+            if api_versions.is_empty() { "none".to_string() } else { api_versions.join(", ") }
+        ),
+        format!("  ffmpeg: {}", describe_binary(&transcoding.ffmpeg)),
+        format!("  ffprobe: {}", describe_binary(&transcoding.ffprobe)),
+    ]
+    .join("\n")
+}
+
 /// Launch the web server with graceful shutdown support.
-pub async fn launch_with_shutdown(shutdown_signal: ShutdownSignal) {
-    launch_rocket_with_shutdown(rocket(), shutdown_signal).await;
+pub async fn launch_with_shutdown(shutdown_signal: ShutdownSignal) -> Result<(), rocket::Error> {
+    launch_rocket_with_shutdown(rocket(), shutdown_signal).await
 }
 
 /// Launch a configured Rocket instance with graceful shutdown support.
+///
+/// If the server fails to start or stops with an error, `shutdown_signal` is triggered so other
+/// components shut down too, and the error is returned to the caller.
 pub async fn launch_rocket_with_shutdown(
     rocket: rocket::Rocket<rocket::Build>,
     shutdown_signal: ShutdownSignal,
-) {
-    let rocket = rocket.ignite().await.expect("Failed to ignite rocket");
+) -> Result<(), rocket::Error> {
+    let rocket = match rocket.ignite().await {
+        Ok(rocket) => rocket,
+        Err(error) => {
+            shutdown_signal.shutdown();
+            return Err(error);
+        }
+    };
+    let database_url = rocket
+        .figment()
+        .extract_inner::<String>("databases.sqlite_db.url")
+        .unwrap_or_else(|_| "unknown".to_string());
+    let api_versions = mounted_api_versions(rocket.routes().map(|route| route.uri.path()));
+    // Probing ffmpeg and ffprobe runs external processes, so keep it off the async runtime.
+    let ffmpeg = current_settings().ffmpeg;
+    match tokio::task::spawn_blocking(move || inspect_transcoding_capability(&ffmpeg)).await {
+        Ok(transcoding) => log::info!(
+            "{}",
+            startup_summary(
+                rocket.config().address,
+                rocket.config().port,
+                rocket.config().tls_enabled(),
+                &database_url,
+                &api_versions,
+                &transcoding,
+            )
+        ),
+        Err(error) => log::warn!("Failed to inspect transcoding capability: {}", error),
+    }
+    let web_signal = rocket.state::<ShutdownSignal>().cloned();
     let rocket_shutdown = rocket.shutdown();
 
     // Start the rocket server
@@ -213,10 +345,27 @@ pub async fn launch_rocket_with_shutdown(
 
     // Create a future that completes when shutdown is signaled
     let shutdown_future = async move {
-        while !shutdown_signal_clone.is_shutdown() {
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        match web_signal.as_ref() {
+            Some(web_signal) => tokio::select! {
+                _ = shutdown_signal_clone.wait_async() => {}
+                _ = web_signal.wait_async() => {
+                    // Forward shutdown requests made by routes, such as an admin restart.
+                    shutdown_signal_clone.shutdown_with_reason(
+                        web_signal.reason().unwrap_or(ShutdownReason::Requested),
+                    );
+                }
+            },
+            None => shutdown_signal_clone.wait_async().await,
+        }
+        match shutdown_signal_clone.reason() {
+            Some(ShutdownReason::Restart) => log::info!(
+                "Web server received restart request; the service manager should restart Koko"
+            ),
+            Some(ShutdownReason::IdleTimeout) => {
+                log::info!("Web server shutting down after the idle timeout")
+            }
+            _ => log::info!("Web server received shutdown signal"),
         }
-        log::info!("Web server received shutdown signal");
     };
 
     // Race between the server and shutdown signal
@@ -225,16 +374,12 @@ pub async fn launch_rocket_with_shutdown(
             log::info!("Rocket server has shut down");
             // Rocket shut down (likely due to SIGINT), signal other components to shut down
             shutdown_signal.shutdown();
-            if let Err(e) = result {
-                log::error!("Web server error: {}", e);
-            }
+            result.map(|_| ())
         }
         _ = shutdown_future => {
-            log::info!("Web server shutting down gracefully");
+            log::info!("Web server shutting down gracefully, draining in-flight requests");
             rocket_shutdown.notify();
-            if let Err(e) = rocket_handle.await {
-                log::error!("Web server error during graceful shutdown: {}", e);
-            }
+            rocket_handle.await.map(|_| ())
         }
     }
 }
@@ -0,0 +1,218 @@
+//! Server administration routes.
+
+// standard imports
+use std::sync::Mutex;
+use std::sync::atomic::{
+    AtomicBool,
+    Ordering,
+};
+use std::time::{
+    Duration,
+    Instant,
+};
+
+// lib imports
+use base64::{
+    Engine as _,
+    engine::general_purpose,
+};
+use rand::RngExt;
+use rocket::State;
+use rocket::get;
+use rocket::http::Status;
+use rocket::post;
+use rocket::put;
+use rocket::request::{
+    self,
+    FromRequest,
+    Request,
+};
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+use schemars::JsonSchema;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+// local imports
+use crate::auth::AdminGuard;
+use crate::signal_handler::{
+    ShutdownReason,
+    ShutdownSignal,
+};
+
+/// How long a restart confirmation token stays valid.
+const RESTART_CONFIRMATION_TTL: Duration = Duration::from_secs(60);
+
+/// Restart confirmation token issued by the first restart request.
+#[derive(Default)]
+pub struct PendingRestart {
+    token: Mutex<Option<(String, Instant)>>,
+}
+
+impl PendingRestart {
+    fn issue(&self) -> Option<String> {
+        let random_bytes: [u8; 24] = rand::rng().random();
+        let token = general_purpose::URL_SAFE_NO_PAD.encode(random_bytes);
+        let mut pending = self.token.lock().ok()?;
+        *pending = Some((token.clone(), Instant::now()));
+        Some(token)
+    }
+
+    fn confirm(
+        &self,
+        token: &str,
+    ) -> bool {
+        let Ok(mut pending) = self.token.lock() else {
+            return false;
+        };
+        let confirmed = matches!(
+            pending.as_ref(),
+            Some((expected, issued_at))
+                if expected == token && issued_at.elapsed() < RESTART_CONFIRMATION_TTL
+        );
+        if confirmed {
+            *pending = None;
+        }
+        confirmed
+    }
+}
+
+/// Restart request payload.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct RestartRequest {
+    /// Token returned by a previous restart request. Omit it to request a new token.
+    pub confirmation_token: Option<String>,
+}
+
+/// Restart response payload.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RestartResponse {
+    /// Whether the restart was confirmed and shutdown has started.
+    pub restarting: bool,
+    /// Token to send back to confirm the restart, when one was issued.
+    pub confirmation_token: Option<String>,
+    /// Seconds until the issued token expires.
+    pub expires_in_secs: Option<u64>,
+}
+
+/// Restart the server through the coordinated shutdown path.
+///
+/// The first call returns a confirmation token. Sending that token back within a minute shuts
+/// the server down with the `Restart` reason so the service manager can start it again.
+#[openapi(tag = "Admin")]
+#[post("/api/v1/admin/restart", format = "json", data = "<request>")]
+pub fn restart_server(
+    _admin_guard: AdminGuard,
+    pending_restart: &State<PendingRestart>,
+    shutdown_signal: &State<ShutdownSignal>,
+    request: Json<RestartRequest>,
+) -> Result<Json<RestartResponse>, Status> {
+    let Some(token) = request.into_inner().confirmation_token else {
+        let token = pending_restart.issue().ok_or(Status::InternalServerError)?;
+        return Ok(Json(RestartResponse {
+            restarting: false,
+            confirmation_token: Some(token),
+            expires_in_secs: Some(RESTART_CONFIRMATION_TTL.as_secs()),
+        }));
+    };
+
+    if !pending_restart.confirm(&token) {
+        return Err(Status::BadRequest);
+    }
+
+    log::info!("Restart confirmed by an administrator");
+    shutdown_signal.shutdown_with_reason(ShutdownReason::Restart);
+    Ok(Json(RestartResponse {
+        restarting: true,
+        confirmation_token: None,
+        expires_in_secs: None,
+    }))
+}
+
+/// Runtime maintenance flag that blocks state-changing routes while reads keep working.
+#[derive(Default)]
+pub struct MaintenanceMode {
+    enabled: AtomicBool,
+}
+
+impl MaintenanceMode {
+    /// Whether maintenance mode is currently enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    /// Enable or disable maintenance mode.
+    pub fn set_enabled(
+        &self,
+        enabled: bool,
+    ) {
+        self.enabled.store(enabled, Ordering::SeqCst);
+    }
+}
+
+/// Request guard for state-changing routes that fails with 503 during maintenance.
+pub struct WritesAllowed;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for WritesAllowed {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let maintenance = request.rocket().state::<MaintenanceMode>();
+        if maintenance.is_some_and(MaintenanceMode::is_enabled) {
+            return request::Outcome::Error((Status::ServiceUnavailable, ()));
+        }
+        request::Outcome::Success(WritesAllowed)
+    }
+}
+
+impl rocket_okapi::request::OpenApiFromRequest<'_> for WritesAllowed {
+    fn from_request_input(
+        _gen: &mut rocket_okapi::gen::OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> rocket_okapi::Result<rocket_okapi::request::RequestHeaderInput> {
+        Ok(rocket_okapi::request::RequestHeaderInput::None)
+    }
+}
+
+/// Maintenance mode request and response payload.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct MaintenanceModeStatus {
+    /// Whether state-changing routes are blocked.
+    pub enabled: bool,
+}
+
+/// Report whether maintenance mode is enabled.
+#[openapi(tag = "Admin")]
+#[get("/api/v1/admin/maintenance")]
+pub fn get_maintenance_mode(
+    _admin_guard: AdminGuard,
+    maintenance: &State<MaintenanceMode>,
+) -> Json<MaintenanceModeStatus> {
+    Json(MaintenanceModeStatus {
+        enabled: maintenance.is_enabled(),
+    })
+}
+
+/// Enable or disable maintenance mode.
+///
+/// While enabled, user and settings changes return 503. Login, browsing, and playback keep
+/// working so backups can run without taking the server offline.
+#[openapi(tag = "Admin")]
+#[put("/api/v1/admin/maintenance", format = "json", data = "<request>")]
+pub fn set_maintenance_mode(
+    _admin_guard: AdminGuard,
+    maintenance: &State<MaintenanceMode>,
+    request: Json<MaintenanceModeStatus>,
+) -> Json<MaintenanceModeStatus> {
+    let enabled = request.into_inner().enabled;
+    maintenance.set_enabled(enabled);
+    log::info!(
+        "Maintenance mode {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+    Json(MaintenanceModeStatus { enabled })
+}
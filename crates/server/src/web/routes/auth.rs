@@ -1,10 +1,15 @@
 //! Routes for the web server.
 
+// standard imports
+use std::net::IpAddr;
+use std::time::Duration;
+
 // lib imports
 use diesel::QueryDsl;
 use diesel::RunQueryDsl;
 use diesel::{
     ExpressionMethods,
+    OptionalExtension,
     SelectableHelper,
 };
 use rocket::http::Status;
@@ -14,6 +19,7 @@ use rocket::serde::{
     json::Json,
 };
 use rocket::{
+    State,
     get,
     post,
 };
@@ -26,6 +32,9 @@ use serde_json::json;
 // local imports
 use crate::auth::{
     AdminGuard,
+    LoginThrottle,
+    SuspiciousLoginEvent,
+    SuspiciousLoginMonitor,
     UserGuard,
 };
 use crate::db::DbConn;
@@ -37,9 +46,50 @@ pub struct LoginForm {
     password: String,
 }
 
+#[derive(Deserialize, JsonSchema)]
+pub struct PinLoginForm {
+    username: String,
+    pin: String,
+}
+
 #[derive(Serialize, JsonSchema)]
 pub struct TokenResponse {
     token: String,
+    refresh_token: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct RefreshForm {
+    refresh_token: String,
+}
+
+/// Issue a new access token and refresh token pair for a user.
+fn issue_tokens(user: &User) -> Result<TokenResponse, Status> {
+    let jwt_keys = match crate::auth::active_jwt_keys() {
+        Ok(jwt_keys) => jwt_keys,
+        Err(e) => {
+            log::error!("Failed to load JWT keys: {}", e);
+            return Err(Status::InternalServerError);
+        }
+    };
+
+    let user_id = user.id.to_string();
+    let credential = crate::auth::credential_fingerprint(&user.password, user.pin.as_deref());
+    let tokens = crate::auth::create_token_with_keys(&user_id, &jwt_keys).and_then(|token| {
+        crate::auth::create_refresh_token(&user_id, &credential, &jwt_keys).map(|refresh_token| {
+            TokenResponse {
+                token,
+                refresh_token,
+            }
+        })
+    });
+    match tokens {
+        Ok(tokens) => Ok(tokens),
+        Err(e) => {
+            log::error!("Failed to create token: {}", e);
+            Err(Status::InternalServerError)
+        }
+    }
 }
 
 #[openapi(tag = "Auth")]
@@ -47,16 +97,23 @@ pub struct TokenResponse {
 pub async fn login(
     db: DbConn,
     login_form: Json<LoginForm>,
+    throttle: &State<LoginThrottle>,
+    monitor: &State<SuspiciousLoginMonitor>,
+    client_ip: Option<IpAddr>,
 ) -> Result<Json<TokenResponse>, Status> {
     use crate::db::schema::users::dsl::*;
 
     let form = login_form.into_inner();
+    if throttle.is_locked(&form.username) {
+        return Err(Status::TooManyRequests);
+    }
     println!("Attempting login for user: {}", form.username);
 
+    let login_name = form.username.clone();
     let user = match db
         .run(move |conn| {
             users
-                .filter(username.eq(form.username))
+                .filter(username.eq(login_name))
                 .select(User::as_select())
                 .first::<User>(conn)
         })
@@ -65,6 +122,7 @@ pub async fn login(
         Ok(user) => user,
         Err(e) => {
             println!("Database error: {}", e);
+            record_failed_login(throttle, monitor, &form.username, client_ip);
             return Err(Status::Unauthorized);
         }
     };
@@ -75,19 +133,138 @@ pub async fn login(
     // Verify password using BCrypt
     if !crate::auth::verify_password(&form.password, &user.password) {
         println!("Password verification failed");
+        record_failed_login(throttle, monitor, &form.username, client_ip);
         return Err(Status::Unauthorized);
     }
+    throttle.record_success(&form.username);
+    monitor.record_success(&form.username);
+    crate::metrics::record_login();
 
-    let token = match crate::auth::create_token(&user.id.to_string(), crate::auth::get_jwt_secret())
-    {
-        Ok(token) => token,
-        Err(e) => {
-            println!("Failed to create token: {}", e);
-            return Err(Status::InternalServerError);
-        }
+    issue_tokens(&user).map(Json)
+}
+
+/// Log in with a user's PIN instead of their password.
+///
+/// Users without a PIN cannot use this route.
+#[openapi(tag = "Auth")]
+#[post("/login_pin", format = "json", data = "<login_form>")]
+pub async fn login_pin(
+    db: DbConn,
+    login_form: Json<PinLoginForm>,
+    throttle: &State<LoginThrottle>,
+    monitor: &State<SuspiciousLoginMonitor>,
+    client_ip: Option<IpAddr>,
+) -> Result<Json<TokenResponse>, Status> {
+    use crate::db::schema::users::dsl::*;
+
+    let form = login_form.into_inner();
+    if throttle.is_locked(&form.username) {
+        return Err(Status::TooManyRequests);
+    }
+    let login_name = form.username.clone();
+    let user = db
+        .run(move |conn| {
+            users
+                .filter(username.eq(login_name))
+                .select(User::as_select())
+                .first::<User>(conn)
+        })
+        .await
+        .ok();
+
+    let verified = user.as_ref().is_some_and(|user| {
+        user.pin
+            .as_deref()
+            .is_some_and(|hashed_pin| crate::auth::verify_password(&form.pin, hashed_pin))
+    });
+    let Some(user) = user.filter(|_| verified) else {
+        record_failed_login(throttle, monitor, &form.username, client_ip);
+        return Err(Status::Unauthorized);
     };
+    throttle.record_success(&form.username);
+    monitor.record_success(&form.username);
+    crate::metrics::record_login();
+
+    issue_tokens(&user).map(Json)
+}
+
+/// Exchange a refresh token for a new access token and refresh token.
+///
+/// Refresh tokens that expired within `api.refresh_grace_secs` are still accepted. A refresh
+/// token is revoked once its user is deleted or their password or PIN changes.
+#[openapi(tag = "Auth")]
+#[post("/refresh", format = "json", data = "<refresh_form>")]
+pub async fn refresh(
+    db: DbConn,
+    refresh_form: Json<RefreshForm>,
+) -> Result<Json<TokenResponse>, Status> {
+    use crate::db::schema::users::dsl::*;
+
+    let jwt_keys = crate::auth::active_jwt_keys().map_err(|e| {
+        log::error!("Failed to load JWT keys: {}", e);
+        Status::InternalServerError
+    })?;
+    let grace = Duration::from_secs(crate::config::current_settings().api.refresh_grace_secs);
+    let claims = crate::auth::decode_refresh_token(&refresh_form.refresh_token, &jwt_keys, grace)
+        .map_err(|_| Status::Unauthorized)?;
+    let user_id: i32 = claims.sub.parse().map_err(|_| Status::Unauthorized)?;
+
+    let user = db
+        .run(move |conn| {
+            users
+                .filter(id.eq(user_id))
+                .select(User::as_select())
+                .first::<User>(conn)
+                .optional()
+        })
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .ok_or(Status::Unauthorized)?;
+    let credential = crate::auth::credential_fingerprint(&user.password, user.pin.as_deref());
+    if claims.credential.as_deref() != Some(credential.as_str()) {
+        return Err(Status::Unauthorized);
+    }
+
+    issue_tokens(&user).map(Json)
+}
+
+/// Count a failed login towards lockout, and raise a `suspicious_login` notification once the
+/// threshold is crossed.
+fn record_failed_login(
+    throttle: &LoginThrottle,
+    monitor: &SuspiciousLoginMonitor,
+    login_name: &str,
+    client_ip: Option<IpAddr>,
+) {
+    crate::metrics::record_failed_login();
+    let settings = crate::config::current_settings().api;
+    throttle.record_failure(
+        login_name,
+        settings.login_lockout_threshold,
+        Duration::from_secs(settings.login_lockout_secs),
+    );
+    let window = Duration::from_secs(settings.suspicious_login_window_secs);
+    if let Some(failed_attempts) =
+        monitor.record_failure(login_name, settings.suspicious_login_threshold, window)
+    {
+        let event = SuspiciousLoginEvent::new(login_name, client_ip, failed_attempts);
+        rocket::tokio::spawn(crate::auth::notify_suspicious_login(
+            event,
+            settings.suspicious_login_webhook_url,
+        ));
+    }
+}
+
+/// Reject non-JSON login requests so the 415 catcher can explain the expected content type.
+#[post("/login", rank = 2)]
+pub fn login_requires_json() -> Status {
+    Status::UnsupportedMediaType
+}
 
-    Ok(Json(TokenResponse { token }))
+/// Reject non-JSON PIN login requests so the 415 catcher can explain the expected content type.
+#[post("/login_pin", rank = 2)]
+pub fn login_pin_requires_json() -> Status {
+    Status::UnsupportedMediaType
 }
 
 #[openapi(tag = "Auth")]
@@ -8,17 +8,214 @@ use std::path::{
 };
 
 // lib imports
+use diesel::RunQueryDsl;
 use rocket::fs::NamedFile;
-use rocket::get;
 use rocket::http::uri::{
     Segments,
     fmt::Path as UriPath,
 };
+use rocket::http::{
+    ContentType,
+    Status,
+};
 use rocket::response::content::RawHtml;
+use rocket::response::status;
+use rocket::serde::json::Json;
+use rocket::{
+    Request,
+    State,
+    catch,
+    get,
+};
+use rocket_okapi::okapi::openapi3::{
+    OpenApi,
+    Server,
+};
+use rocket_okapi::openapi;
+use schemars::JsonSchema;
+use serde::Serialize;
 
 // local imports
+use crate::auth::AdminGuard;
+use crate::db::DbConn;
 use crate::globals;
 
+/// Default number of items returned by paginated list endpoints.
+pub const DEFAULT_PAGE_LIMIT: u32 = 100;
+/// Largest page size accepted by paginated list endpoints.
+pub const MAX_PAGE_LIMIT: u32 = 1_000;
+
+/// Paginated list response shared by list endpoints.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Paginated<T> {
+    /// Items in the requested page.
+    pub items: Vec<T>,
+    /// Total number of items available, independent of the page size.
+    pub total: u64,
+    /// Page size used for this response.
+    pub limit: u32,
+    /// Number of items skipped before this page.
+    pub offset: u32,
+}
+
+impl<T> Paginated<T> {
+    /// Build a page by slicing an already-loaded list.
+    pub fn from_items(
+        items: Vec<T>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> Self {
+        let limit = page_limit(limit);
+        let offset = offset.unwrap_or_default();
+        let total = items.len() as u64;
+        let items = items
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect();
+
+        Self {
+            items,
+            total,
+            limit,
+            offset,
+        }
+    }
+}
+
+/// Clamp a requested page size to the supported range.
+pub fn page_limit(limit: Option<u32>) -> u32 {
+    limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT)
+}
+
+/// Structured error body returned by the API error catchers.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ApiError {
+    /// HTTP status code.
+    pub status: u16,
+    /// Short machine-readable error name.
+    pub error: String,
+    /// Human-readable explanation of what the client should change.
+    pub message: String,
+}
+
+fn api_error(
+    status: Status,
+    error: &str,
+    message: String,
+) -> status::Custom<Json<ApiError>> {
+    status::Custom(
+        status,
+        Json(ApiError {
+            status: status.code,
+            error: error.to_string(),
+            message,
+        }),
+    )
+}
+
+/// Explain that the route only accepts JSON request bodies.
+#[catch(415)]
+pub fn unsupported_media_type(request: &Request<'_>) -> status::Custom<Json<ApiError>> {
+    let received = request
+        .content_type()
+        .map(|content_type| content_type.to_string())
+        .unwrap_or_else(|| "none".to_string());
+    api_error(
+        Status::UnsupportedMediaType,
+        "unsupported_media_type",
+        format!(
+            "{} expects a JSON body sent with `Content-Type: application/json` (received: \
+             {received})",
+            request.uri().path()
+        ),
+    )
+}
+
+/// Explain that a JSON request body or a query or path parameter could not be parsed.
+#[catch(422)]
+pub fn unprocessable_entity(request: &Request<'_>) -> status::Custom<Json<ApiError>> {
+    let path = request.uri().path();
+    let is_json = request
+        .content_type()
+        .is_some_and(|content_type| content_type.is_json());
+    let message = if is_json {
+        format!(
+            "The JSON body sent to {path} is missing required fields or has fields of the wrong \
+             type"
+        )
+    } else {
+        format!("A query or path parameter sent to {path} is missing or has the wrong type")
+    };
+    api_error(Status::UnprocessableEntity, "unprocessable_entity", message)
+}
+
+/// OpenAPI spec generated for the API routes, served by [`openapi_json`].
+pub struct ApiSpec(pub OpenApi);
+
+/// Serve the OpenAPI spec with a relative `servers` entry.
+///
+/// Swagger UI and RapiDoc use this entry as the base URL for "Try it out" requests. `./`
+/// resolves against the URL the spec was fetched from, so it stays correct when the server binds
+/// `0.0.0.0` or a reverse proxy serves it under a subpath.
+#[get("/openapi.json")]
+pub fn openapi_json(spec: &State<ApiSpec>) -> Json<OpenApi> {
+    let mut spec = spec.0.clone();
+    spec.servers = vec![Server {
+        url: "./".to_string(),
+        description: Some("This server".to_string()),
+        ..Default::default()
+    }];
+    Json(spec)
+}
+
+/// Health probe body returned by [`health`] and [`ready`].
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct HealthStatus {
+    /// Always `ok` when the probe succeeds.
+    pub status: String,
+    /// Server version.
+    pub version: String,
+}
+
+impl HealthStatus {
+    fn ok() -> Self {
+        Self {
+            status: "ok".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+/// Report that the server is running, without touching the database.
+#[openapi(tag = "Health")]
+#[get("/health")]
+pub fn health() -> Json<HealthStatus> {
+    Json(HealthStatus::ok())
+}
+
+/// Report whether the server can reach its database and serve requests.
+///
+/// Returns 503 when the database cannot be queried.
+#[openapi(tag = "Health")]
+#[get("/ready")]
+pub async fn ready(db: DbConn) -> Result<Json<HealthStatus>, Status> {
+    db.run(|conn| diesel::sql_query("SELECT 1").execute(conn))
+        .await
+        .map_err(|error| {
+            log::warn!("Readiness check failed to query the database: {}", error);
+            Status::ServiceUnavailable
+        })?;
+
+    Ok(Json(HealthStatus::ok()))
+}
+
+/// Serve server metrics in the Prometheus text format.
+#[get("/metrics")]
+pub fn metrics(_admin_guard: AdminGuard) -> (ContentType, String) {
+    (ContentType::Plain, crate::metrics::render())
+}
+
 #[get("/")]
 pub async fn index() -> Result<NamedFile, RawHtml<String>> {
     let index_path = web_client_index_path();
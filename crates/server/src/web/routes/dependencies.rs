@@ -11,6 +11,7 @@ use serde::Serialize;
 
 // local imports
 use crate::dependencies;
+use crate::web::routes::common::Paginated;
 
 // Create your own response type that derives Serialize and JsonSchema.
 #[derive(Debug, Serialize, JsonSchema)]
@@ -32,12 +33,15 @@ impl From<Package> for PackageResponse {
 }
 
 #[openapi(tag = "Dependencies")]
-#[get("/dependencies")]
-pub fn get_dependencies() -> Result<Json<Vec<PackageResponse>>, Status> {
+#[get("/dependencies?<limit>&<offset>")]
+pub fn get_dependencies(
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<Json<Paginated<PackageResponse>>, Status> {
     let deps = dependencies::get_dependencies().map_err(|e| {
         log::error!("Failed to get dependencies: {}", e);
         Status::InternalServerError
     })?;
     let response = deps.into_iter().map(PackageResponse::from).collect();
-    Ok(Json(response))
+    Ok(Json(Paginated::from_items(response, limit, offset)))
 }
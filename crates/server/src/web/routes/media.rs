@@ -159,6 +159,7 @@ use crate::metadata::{
     upsert_secondary_collection_theme_song_url,
 };
 use crate::utils::current_timestamp;
+use crate::web::routes::admin::WritesAllowed;
 
 pub enum SessionStream {
     File(RangedFile),
@@ -314,6 +315,21 @@ async fn open_ranged_file(
     })
 }
 
+/// Stop a removed playback session's transcode and delete its transcode cache in the background.
+async fn clean_up_playback_session(session_id: &str) {
+    stop_active_transcode(session_id).await;
+
+    let settings = current_settings();
+    let session_dir = PathBuf::from(&settings.general.data_dir)
+        .join("transcode_cache")
+        .join(session_id);
+
+    // Background cleanup
+    tokio::spawn(async move {
+        let _ = tokio::fs::remove_dir_all(session_dir).await;
+    });
+}
+
 async fn stop_active_transcode(session_id: &str) -> bool {
     let handle = ACTIVE_TRANSCODE_TASKS.lock().await.remove(session_id);
     if let Some(handle) = handle {
@@ -529,6 +545,23 @@ static ACTIVE_TRANSCODE_TASKS: Lazy<
     tokio::sync::Mutex<HashMap<String, tokio::task::JoinHandle<()>>>,
 > = Lazy::new(|| tokio::sync::Mutex::new(HashMap::new()));
 
+/// Return the number of playback sessions that streamed within
+/// `server.playback_session_idle_secs`.
+///
+/// This never blocks, so it is safe to call from any context. It returns `None` while a request
+/// holds the session registry.
+pub fn active_playback_session_count() -> Option<usize> {
+    let sessions = ACTIVE_PLAYBACK_SESSIONS.try_read().ok()?;
+    let idle_secs = current_settings().server.playback_session_idle_secs;
+    let now = current_timestamp();
+    Some(
+        sessions
+            .values()
+            .filter(|session| !crate::media::playback_session_is_idle(session, now, idle_secs))
+            .count(),
+    )
+}
+
 #[derive(Debug, Clone)]
 struct MetadataRefreshActivityRecord {
     activity: SystemActivity,
@@ -2892,6 +2925,36 @@ fn user_preferred_metadata_languages(
     Ok(crate::web::routes::user::parse_preferred_metadata_languages(&stored))
 }
 
+/// Return the concurrent playback-session limit that applies to a user, if any.
+///
+/// Anonymous sessions and administrators are not limited.
+fn user_playback_session_limit(
+    conn: &mut rocket_sync_db_pools::diesel::SqliteConnection,
+    user_id: Option<i32>,
+    max_sessions: u32,
+) -> Result<Option<usize>, diesel::result::Error> {
+    use crate::db::schema::users::dsl as users_dsl;
+    use diesel::{
+        ExpressionMethods,
+        OptionalExtension,
+        QueryDsl,
+        RunQueryDsl,
+    };
+
+    let Some(user_id) = user_id.filter(|_| max_sessions > 0) else {
+        return Ok(None);
+    };
+
+    let is_admin = users_dsl::users
+        .filter(users_dsl::id.eq(user_id))
+        .select(users_dsl::admin)
+        .first::<bool>(conn)
+        .optional()?
+        .unwrap_or(false);
+
+    Ok((!is_admin).then_some(max_sessions as usize))
+}
+
 async fn load_item_library_metadata_providers(
     db: &DbConn,
     library_id: i32,
@@ -3053,6 +3116,7 @@ pub async fn get_server_capabilities(
 #[post("/api/v1/libraries/<library_id>/scan")]
 pub async fn scan_library(
     db: DbConn,
+    _writes: WritesAllowed,
     library_id: i32,
 ) -> Result<Json<PersistedLibrarySummary>, Status> {
     let settings = current_settings();
@@ -3126,6 +3190,7 @@ pub async fn scan_library(
 #[delete("/api/v1/libraries/<library_id>/missing")]
 pub async fn delete_library_missing_items(
     db: DbConn,
+    _writes: WritesAllowed,
     library_id: i32,
 ) -> Result<Json<MissingItemsCleanupResponse>, Status> {
     let exists = db
@@ -3452,6 +3517,10 @@ pub async fn get_item(
 }
 
 /// Create a new playback session.
+///
+/// Returns 429 when a non-admin user already has `server.max_playback_sessions_per_user`
+/// sessions open; deleting one of them frees the slot, as does leaving it without stream
+/// requests for `server.playback_session_idle_secs`.
 #[openapi(tag = "Media")]
 #[post("/api/v1/sessions", format = "json", data = "<request>")]
 pub async fn create_session(
@@ -3461,6 +3530,12 @@ pub async fn create_session(
 ) -> Result<Json<crate::media::PlaybackSession>, Status> {
     let payload = request.into_inner();
     let user_id = current_user_id(user_guard.as_ref()).unwrap_or(None);
+    let server_settings = current_settings().server;
+    let max_sessions = server_settings.max_playback_sessions_per_user;
+    let session_limit = db
+        .run(move |conn| user_playback_session_limit(conn, user_id, max_sessions))
+        .await
+        .map_err(|_| Status::InternalServerError)?;
     let preferred_languages = db
         .run(move |conn| user_preferred_metadata_languages(conn, user_id))
         .await
@@ -3492,13 +3567,40 @@ pub async fn create_session(
         client_profile: payload.client_profile,
         decision,
         created_at: current_timestamp(),
+        last_active_at: current_timestamp(),
         audio_stream_index,
     };
 
-    ACTIVE_PLAYBACK_SESSIONS
-        .write()
-        .await
-        .insert(session_id, session.clone());
+    let mut sessions = ACTIVE_PLAYBACK_SESSIONS.write().await;
+    let idle_session_ids = crate::media::prune_idle_playback_sessions(
+        &mut sessions,
+        session.created_at,
+        server_settings.playback_session_idle_secs,
+    );
+    let user_sessions = sessions
+        .values()
+        .filter(|existing| existing.user_id == user_id)
+        .count();
+    let exceeded_limit = session_limit.filter(|limit| user_sessions >= *limit);
+    if exceeded_limit.is_none() {
+        sessions.insert(session_id, session.clone());
+    }
+    crate::metrics::set_active_playback_sessions(sessions.len());
+    drop(sessions);
+
+    for idle_session_id in idle_session_ids {
+        log::info!("Dropping idle playback session {}", idle_session_id);
+        clean_up_playback_session(&idle_session_id).await;
+    }
+    if let Some(limit) = exceeded_limit {
+        log::warn!(
+            "Refusing playback session for user {:?}: {} of {} concurrent sessions in use",
+            user_id,
+            user_sessions,
+            limit
+        );
+        return Err(Status::TooManyRequests);
+    }
 
     Ok(Json(session))
 }
@@ -3507,21 +3609,15 @@ pub async fn create_session(
 #[openapi(tag = "Media")]
 #[delete("/api/v1/sessions/<session_id>")]
 pub async fn delete_session(session_id: String) -> Status {
-    let removed = ACTIVE_PLAYBACK_SESSIONS.write().await.remove(&session_id);
+    let removed = {
+        let mut sessions = ACTIVE_PLAYBACK_SESSIONS.write().await;
+        let removed = sessions.remove(&session_id);
+        crate::metrics::set_active_playback_sessions(sessions.len());
+        removed
+    };
 
     if removed.is_some() {
-        stop_active_transcode(&session_id).await;
-
-        let settings = current_settings();
-        let session_dir = PathBuf::from(&settings.general.data_dir)
-            .join("transcode_cache")
-            .join(&session_id);
-
-        // Background cleanup
-        tokio::spawn(async move {
-            let _ = tokio::fs::remove_dir_all(session_dir).await;
-        });
-
+        clean_up_playback_session(&session_id).await;
         Status::NoContent
     } else {
         Status::NotFound
@@ -3537,12 +3633,13 @@ pub async fn get_session_stream(
     start_ms: Option<i64>,
     audio_stream_index: Option<usize>,
 ) -> Result<SessionStream, Status> {
-    let session = ACTIVE_PLAYBACK_SESSIONS
-        .read()
-        .await
-        .get(&session_id)
-        .cloned()
-        .ok_or(Status::NotFound)?;
+    let session = {
+        let mut sessions = ACTIVE_PLAYBACK_SESSIONS.write().await;
+        let session = sessions.get_mut(&session_id).ok_or(Status::NotFound)?;
+        session.last_active_at = current_timestamp();
+        session.clone()
+    };
+    crate::metrics::record_stream_request();
     let selected_audio_stream_index = audio_stream_index.or(session.audio_stream_index);
 
     if session.decision.can_direct_play && selected_audio_stream_index.unwrap_or_default() == 0 {
@@ -3700,6 +3797,7 @@ pub async fn stream_item(
     if !decision.can_direct_play {
         return Err(Status::Conflict);
     }
+    crate::metrics::record_stream_request();
 
     let source_path = db
         .run(move |conn| resolve_media_item_source_path(conn, item_id))
@@ -4043,6 +4141,7 @@ pub async fn search_item_metadata(
 )]
 pub async fn link_item_metadata(
     db: DbConn,
+    _writes: WritesAllowed,
     item_id: i32,
     request: Json<LinkMetadataRequest>,
 ) -> Result<Json<ItemMetadataSummary>, Status> {
@@ -4169,6 +4268,7 @@ pub async fn link_item_metadata(
 #[post("/api/v1/items/<item_id>/metadata/refresh")]
 pub async fn refresh_item_metadata(
     db: DbConn,
+    _writes: WritesAllowed,
     item_id: i32,
 ) -> Result<Json<ItemMetadataSummary>, Status> {
     let settings = current_settings();
@@ -4330,6 +4430,7 @@ async fn run_manual_library_metadata_refresh(
 #[post("/api/v1/libraries/<library_id>/metadata/refresh")]
 pub async fn refresh_library_metadata(
     db: DbConn,
+    _writes: WritesAllowed,
     library_id: i32,
 ) -> Result<Json<PersistedLibrarySummary>, Status> {
     let settings = current_settings();
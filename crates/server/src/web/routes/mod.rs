@@ -1,6 +1,7 @@
 //! Common routes module for the web server.
 
 // modules
+pub mod admin;
 pub mod auth;
 pub mod common;
 pub mod dependencies;
@@ -9,16 +10,30 @@ pub mod settings;
 pub mod user;
 
 // lib imports
-use rocket::routes;
-use rocket_okapi::openapi_get_routes; // this is a replacement for the rocket::routes macro
+use rocket::{
+    catchers,
+    routes,
+};
+use rocket_okapi::okapi::openapi3::OpenApi;
+use rocket_okapi::openapi_get_routes_spec; // this is a replacement for the rocket::routes macro
 
-pub fn api_routes() -> Vec<rocket::Route> {
-    openapi_get_routes![
+/// API routes and the OpenAPI spec generated for them.
+///
+/// The spec is served by [`common::openapi_json`], which adds the `servers` block.
+pub fn api_routes_and_spec() -> (Vec<rocket::Route>, OpenApi) {
+    openapi_get_routes_spec![
+        admin::restart_server,
+        admin::get_maintenance_mode,
+        admin::set_maintenance_mode,
         auth::login,
+        auth::login_pin,
+        auth::refresh,
         auth::logout,
         auth::jwt_test,
         auth::admin_test,
         auth::user_info,
+        common::health,
+        common::ready,
         dependencies::get_dependencies,
         media::get_server_capabilities,
         media::get_system_activities,
@@ -45,6 +60,7 @@ pub fn api_routes() -> Vec<rocket::Route> {
         settings::get_settings,
         settings::get_logs,
         settings::clear_metadata_cache,
+        settings::check_certificates,
         settings::run_scheduled_task,
         settings::update_settings,
         settings::add_library,
@@ -52,12 +68,26 @@ pub fn api_routes() -> Vec<rocket::Route> {
         user::get_bootstrap,
         user::list_users,
         user::update_user,
+        user::update_user_admin,
+        user::delete_user,
         user::create_user,
     ]
 }
 
+pub fn api_catchers() -> Vec<rocket::Catcher> {
+    catchers![
+        common::unsupported_media_type,
+        common::unprocessable_entity
+    ]
+}
+
 pub fn spa_routes() -> Vec<rocket::Route> {
     routes![
+        auth::login_requires_json,
+        auth::login_pin_requires_json,
+        user::create_user_requires_json,
+        common::openapi_json,
+        common::metrics,
         common::index,
         common::spa_asset,
         user::get_user_profile_image,
@@ -25,10 +25,17 @@ use serde::{
 };
 
 // local imports
+use crate::auth::AdminGuard;
+use crate::certs::{
+    CertificateSummary,
+    certificate_paths,
+    inspect_certificate_pair,
+};
 use crate::config::{
     MediaLibrarySettings,
     Settings,
     current_settings,
+    merge_api_secret_state,
     merge_metadata_provider_secret_state,
     replace_current_settings,
     save_database_settings,
@@ -36,6 +43,7 @@ use crate::config::{
     settings_file_path,
     settings_for_api_response,
     settings_with_persisted_secrets,
+    validate_settings,
 };
 use crate::db::DbConn;
 use crate::globals;
@@ -50,6 +58,7 @@ use crate::media::{
     remove_library_setting,
     replace_library_settings,
 };
+use crate::web::routes::admin::WritesAllowed;
 
 static STRUCTURED_LOG_LINE_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(concat!(
@@ -86,6 +95,21 @@ pub struct ScheduledTaskRunResponse {
     pub message: String,
 }
 
+/// TLS certificate check response.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CertificateCheckResponse {
+    /// Certificate path resolved from the server settings.
+    pub cert_path: String,
+    /// Private key path resolved from the server settings.
+    pub key_path: String,
+    /// Whether the certificate and key loaded and match each other.
+    pub valid: bool,
+    /// Certificate details, when the pair is valid.
+    pub certificate: Option<CertificateSummary>,
+    /// Reason the pair failed to load or match.
+    pub error: Option<String>,
+}
+
 /// Add-library request payload.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct AddLibraryRequest {
@@ -290,7 +314,9 @@ pub async fn get_settings(db: DbConn) -> Result<Json<SettingsResponse>, Status>
 /// Clear cached provider metadata responses.
 #[openapi(tag = "Settings")]
 #[post("/api/v1/settings/metadata-cache/clear")]
-pub fn clear_metadata_cache() -> Result<Json<MetadataCacheClearResponse>, Status> {
+pub fn clear_metadata_cache(
+    _writes: WritesAllowed
+) -> Result<Json<MetadataCacheClearResponse>, Status> {
     let data_dir = current_settings().general.data_dir;
     let removed_files =
         crate::metadata::clear_metadata_response_cache(&data_dir).map_err(|error| {
@@ -300,11 +326,31 @@ pub fn clear_metadata_cache() -> Result<Json<MetadataCacheClearResponse>, Status
     Ok(Json(MetadataCacheClearResponse { removed_files }))
 }
 
+/// Load the configured TLS certificate and key and report whether they match.
+#[openapi(tag = "Settings")]
+#[get("/api/v1/debug/certs")]
+pub fn check_certificates(_admin_guard: AdminGuard) -> Json<CertificateCheckResponse> {
+    let (cert_path, key_path) = certificate_paths(&current_settings());
+    let (certificate, error) = match inspect_certificate_pair(&cert_path, &key_path) {
+        Ok(summary) => (Some(summary), None),
+        Err(error) => (None, Some(error)),
+    };
+
+    Json(CertificateCheckResponse {
+        cert_path: normalize_display_path(&cert_path),
+        key_path: normalize_display_path(&key_path),
+        valid: certificate.is_some(),
+        certificate,
+        error,
+    })
+}
+
 /// Start one scheduled task immediately.
 #[openapi(tag = "Settings")]
 #[post("/api/v1/scheduled-tasks/<task_id>/run")]
 pub fn run_scheduled_task(
     db: DbConn,
+    _writes: WritesAllowed,
     task_id: &str,
 ) -> Result<Json<ScheduledTaskRunResponse>, Status> {
     let message = match task_id {
@@ -355,15 +401,26 @@ pub fn get_logs(
 }
 
 /// Replace the full settings snapshot and persist it to disk.
+///
+/// Only admins may write settings, since they include security settings such as JWT signing and
+/// login lockout. Settings that cannot work together, such as a minimum username length above
+/// the maximum, are rejected with 400.
 #[openapi(tag = "Settings")]
 #[put("/api/v1/settings", format = "json", data = "<settings>")]
 pub async fn update_settings(
+    _admin_guard: AdminGuard,
     db: DbConn,
+    _writes: WritesAllowed,
     settings: Json<Settings>,
 ) -> Result<Json<SettingsResponse>, Status> {
     let mut settings = settings.into_inner();
+    validate_settings(&settings).map_err(|error| {
+        log::warn!("Rejected settings update: {}", error);
+        Status::BadRequest
+    })?;
     let existing_settings = current_settings();
     merge_metadata_provider_secret_state(&mut settings, &existing_settings);
+    merge_api_secret_state(&mut settings, &existing_settings);
     let settings_for_database = settings_with_persisted_secrets(&settings).map_err(|error| {
         log::error!("Failed to persist provider credentials: {}", error);
         Status::InternalServerError
@@ -415,6 +472,7 @@ pub async fn update_settings(
 #[post("/api/v1/settings/libraries", format = "json", data = "<request>")]
 pub async fn add_library(
     db: DbConn,
+    _writes: WritesAllowed,
     request: Json<AddLibraryRequest>,
 ) -> Result<Json<SettingsResponse>, Status> {
     let mut library = request.into_inner().library;
@@ -439,6 +497,7 @@ pub async fn add_library(
 #[delete("/api/v1/settings/libraries/<library_index>")]
 pub async fn remove_library(
     db: DbConn,
+    _writes: WritesAllowed,
     library_index: usize,
 ) -> Result<Json<SettingsResponse>, Status> {
     let removed = db
@@ -20,9 +20,12 @@ use diesel::{
     RunQueryDsl,
     SelectableHelper,
 };
+use rocket::State;
+use rocket::delete;
 use rocket::fs::NamedFile;
 use rocket::get;
 use rocket::http::Status;
+use rocket::patch;
 use rocket::post;
 use rocket::put;
 use rocket::serde::{
@@ -41,15 +44,27 @@ use sha2::{
 // local imports
 use crate::auth::{
     AdminGuard,
+    AdminStatusCache,
     UserGuard,
 };
-use crate::config::current_settings;
-use crate::db::DbConn;
+use crate::config::{
+    ApiSettings,
+    current_settings,
+};
 use crate::db::models::User;
+use crate::db::{
+    DbConn,
+    LazyDbConn,
+};
 use crate::globals::{
     CURRENT_ENV,
     Environment,
 };
+use crate::web::routes::admin::WritesAllowed;
+use crate::web::routes::common::{
+    Paginated,
+    page_limit,
+};
 
 const PROFILE_IMAGE_MAX_BYTES: usize = 2 * 1024 * 1024;
 const PROFILE_IMAGE_ROUTE_PREFIX: &str = "/api/v1/user-profile-images/";
@@ -75,6 +90,11 @@ pub struct UpdateUserForm {
     pub preferred_metadata_languages: Option<Vec<String>>,
 }
 
+#[derive(Deserialize, JsonSchema)]
+pub struct UpdateUserAdminForm {
+    pub admin: bool,
+}
+
 #[derive(Deserialize, JsonSchema)]
 pub struct ProfileImageUploadForm {
     pub mime_type: String,
@@ -138,24 +158,181 @@ pub async fn get_bootstrap(
 }
 
 #[openapi(tag = "Users")]
-#[get("/api/v1/users")]
+#[get("/api/v1/users?<limit>&<offset>")]
 pub async fn list_users(
     db: DbConn,
     _admin_guard: AdminGuard,
-) -> Result<Json<Vec<UserSummary>>, Status> {
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<Json<Paginated<UserSummary>>, Status> {
     use crate::db::schema::users::dsl::*;
 
-    let users_list = db
-        .run(|conn| {
-            users
+    let page_size = page_limit(limit);
+    let page_offset = offset.unwrap_or_default();
+    let (total, users_list) = db
+        .run(move |conn| {
+            let total = users.count().get_result::<i64>(conn)?;
+            let users_list = users
                 .order(username.asc())
+                .limit(i64::from(page_size))
+                .offset(i64::from(page_offset))
                 .select(User::as_select())
-                .load::<User>(conn)
+                .load::<User>(conn)?;
+            Ok::<_, diesel::result::Error>((total, users_list))
         })
         .await
         .map_err(|_| Status::InternalServerError)?;
 
-    Ok(Json(users_list.into_iter().map(user_summary).collect()))
+    Ok(Json(Paginated {
+        items: users_list.into_iter().map(user_summary).collect(),
+        total: u64::try_from(total).unwrap_or_default(),
+        limit: page_size,
+        offset: page_offset,
+    }))
+}
+
+/// Promote or demote a user without touching the rest of their profile. The last remaining
+/// admin cannot be demoted.
+#[openapi(tag = "Users")]
+#[patch(
+    "/api/v1/users/<target_user_id>",
+    format = "json",
+    data = "<admin_form>"
+)]
+pub async fn update_user_admin(
+    db: DbConn,
+    _admin_guard: AdminGuard,
+    _writes: WritesAllowed,
+    admin_cache: &State<AdminStatusCache>,
+    target_user_id: i32,
+    admin_form: Json<UpdateUserAdminForm>,
+) -> Result<Json<UserSummary>, Status> {
+    use crate::db::schema::users::dsl as users_dsl;
+
+    let next_admin = admin_form.into_inner().admin;
+    let updated_user = db
+        .run(move |conn| {
+            conn.immediate_transaction(|conn| {
+                let existing_user = users_dsl::users
+                    .filter(users_dsl::id.eq(target_user_id))
+                    .select(User::as_select())
+                    .first::<User>(conn)
+                    .optional()?
+                    .ok_or(AdminUpdateError::NotFound)?;
+
+                if !next_admin {
+                    ensure_not_last_admin(conn, &existing_user)?;
+                }
+
+                diesel::update(users_dsl::users.filter(users_dsl::id.eq(target_user_id)))
+                    .set(users_dsl::admin.eq(next_admin))
+                    .execute(conn)?;
+
+                Ok(users_dsl::users
+                    .filter(users_dsl::id.eq(target_user_id))
+                    .select(User::as_select())
+                    .first::<User>(conn)?)
+            })
+        })
+        .await
+        .map_err(|error| {
+            if let AdminUpdateError::Database(error) = &error {
+                log::error!("Failed to update admin status: {}", error);
+            }
+            error.status()
+        })?;
+
+    admin_cache.invalidate(target_user_id);
+
+    Ok(Json(user_summary(updated_user)))
+}
+
+/// Delete a user account. The last remaining admin cannot be deleted.
+#[openapi(tag = "Users")]
+#[delete("/api/v1/users/<target_user_id>")]
+pub async fn delete_user(
+    db: DbConn,
+    _admin_guard: AdminGuard,
+    _writes: WritesAllowed,
+    admin_cache: &State<AdminStatusCache>,
+    target_user_id: i32,
+) -> Result<Status, Status> {
+    use crate::db::schema::users::dsl as users_dsl;
+
+    let deleted_user = db
+        .run(move |conn| {
+            conn.immediate_transaction(|conn| {
+                let existing_user = users_dsl::users
+                    .filter(users_dsl::id.eq(target_user_id))
+                    .select(User::as_select())
+                    .first::<User>(conn)
+                    .optional()?
+                    .ok_or(AdminUpdateError::NotFound)?;
+
+                ensure_not_last_admin(conn, &existing_user)?;
+
+                diesel::delete(users_dsl::users.filter(users_dsl::id.eq(target_user_id)))
+                    .execute(conn)?;
+                Ok(existing_user)
+            })
+        })
+        .await
+        .map_err(|error| {
+            if let AdminUpdateError::Database(error) = &error {
+                log::error!("Failed to delete user {}: {}", target_user_id, error);
+            }
+            error.status()
+        })?;
+
+    admin_cache.invalidate(target_user_id);
+    if let Some(profile_image_path) = deleted_user.profile_image_path.as_deref() {
+        let _ = remove_managed_profile_image(profile_image_path).await;
+    }
+
+    Ok(Status::Ok)
+}
+
+enum AdminUpdateError {
+    NotFound,
+    LastAdmin,
+    Database(diesel::result::Error),
+}
+
+impl AdminUpdateError {
+    /// Response status shared by every route that changes or removes users.
+    fn status(&self) -> Status {
+        match self {
+            AdminUpdateError::NotFound => Status::NotFound,
+            AdminUpdateError::LastAdmin => Status::Conflict,
+            AdminUpdateError::Database(_) => Status::InternalServerError,
+        }
+    }
+}
+
+/// Refuse to demote or delete `user` when they are the only remaining admin.
+fn ensure_not_last_admin(
+    conn: &mut rocket_sync_db_pools::diesel::SqliteConnection,
+    user: &User,
+) -> Result<(), AdminUpdateError> {
+    use crate::db::schema::users::dsl as users_dsl;
+
+    if !user.admin {
+        return Ok(());
+    }
+    let admin_count = users_dsl::users
+        .filter(users_dsl::admin.eq(true))
+        .count()
+        .get_result::<i64>(conn)?;
+    if admin_count <= 1 {
+        return Err(AdminUpdateError::LastAdmin);
+    }
+    Ok(())
+}
+
+impl From<diesel::result::Error> for AdminUpdateError {
+    fn from(error: diesel::result::Error) -> Self {
+        AdminUpdateError::Database(error)
+    }
 }
 
 #[openapi(tag = "Users")]
@@ -167,6 +344,8 @@ pub async fn list_users(
 pub async fn update_user(
     db: DbConn,
     _admin_guard: AdminGuard,
+    _writes: WritesAllowed,
+    admin_cache: &State<AdminStatusCache>,
     target_user_id: i32,
     user_form: Json<UpdateUserForm>,
 ) -> Result<Json<UserSummary>, Status> {
@@ -174,7 +353,7 @@ pub async fn update_user(
 
     let form = user_form.into_inner();
     let next_username = form.username.trim().to_string();
-    if next_username.is_empty() {
+    if !is_valid_username(&current_settings().api, &next_username) {
         return Err(Status::BadRequest);
     }
     let next_admin = form.admin;
@@ -201,15 +380,8 @@ pub async fn update_user(
                 .map_err(|_| Status::InternalServerError)?
                 .ok_or(Status::NotFound)?;
 
-            if existing_user.admin && !next_admin {
-                let admin_count = users_dsl::users
-                    .filter(users_dsl::admin.eq(true))
-                    .count()
-                    .get_result::<i64>(conn)
-                    .map_err(|_| Status::InternalServerError)?;
-                if admin_count <= 1 {
-                    return Err(Status::BadRequest);
-                }
+            if !next_admin {
+                ensure_not_last_admin(conn, &existing_user).map_err(|error| error.status())?;
             }
 
             let conflicting_username = users_dsl::users
@@ -267,6 +439,7 @@ pub async fn update_user(
             return Err(error);
         }
     };
+    admin_cache.invalidate(target_user_id);
 
     if updated_user.profile_image_path != existing_profile_image_path {
         if let Some(old_path) = existing_profile_image_path.as_deref() {
@@ -277,17 +450,52 @@ pub async fn update_user(
     Ok(Json(user_summary(updated_user)))
 }
 
+/// Check a trimmed username against the configured length and charset policy.
+fn is_valid_username(
+    settings: &ApiSettings,
+    username: &str,
+) -> bool {
+    let length = username.chars().count();
+    if username.is_empty()
+        || length < settings.username_min_length as usize
+        || length > settings.username_max_length as usize
+    {
+        return false;
+    }
+
+    !settings.username_restrict_charset
+        || username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+/// A PIN is 4 to 6 ASCII digits; leading zeros are significant and signs or spaces are rejected.
+fn is_valid_pin(pin: &str) -> bool {
+    (4..=6).contains(&pin.len()) && pin.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Reject non-JSON user creation requests so the 415 catcher can explain the expected content type.
+#[post("/create_user", rank = 2)]
+pub fn create_user_requires_json() -> Status {
+    Status::UnsupportedMediaType
+}
+
 #[openapi(tag = "Users")]
 #[post("/create_user", format = "json", data = "<user_form>")]
 pub async fn create_user(
-    db: DbConn,
-    user_form: Json<CreateUserForm>,
     auth_guard: Option<AdminGuard>,
+    // Check out connections only around the queries, never across the bcrypt hashing below.
+    db: LazyDbConn<'_>,
+    user_form: Json<CreateUserForm>,
+    _writes: WritesAllowed,
 ) -> Result<&'static str, Status> {
     use crate::db::schema::users::dsl::*;
 
     // Check if this is the first user (no authentication required)
     let existing_count = db
+        .get()
+        .await
+        .ok_or(Status::ServiceUnavailable)?
         .run(|conn| users.count().get_result::<i64>(conn))
         .await
         .unwrap_or(0);
@@ -299,30 +507,24 @@ pub async fn create_user(
 
     let form = user_form.into_inner();
     let next_username = form.username.trim().to_string();
-    if next_username.is_empty() {
+    if !is_valid_username(&current_settings().api, &next_username) {
+        return Err(Status::BadRequest);
+    }
+    if form
+        .pin
+        .as_deref()
+        .is_some_and(|pin_value| !is_valid_pin(pin_value))
+    {
         return Err(Status::BadRequest);
     }
 
     // Hash password using BCrypt
-    let hashed_password = match crate::auth::hash_password(&form.password) {
-        Ok(hash) => hash,
-        Err(_) => return Err(Status::InternalServerError),
-    };
+    let hashed_password = hash_credential(form.password).await?;
 
     // Hash PIN if provided
-    let hashed_pin = if let Some(pin_value) = form.pin {
-        if pin_value.parse::<i32>().is_err() {
-            return Err(Status::BadRequest);
-        }
-        if pin_value.len() < 4 || pin_value.len() > 6 {
-            return Err(Status::BadRequest);
-        }
-        match crate::auth::hash_password(&pin_value) {
-            Ok(hash) => Some(hash),
-            Err(_) => return Err(Status::InternalServerError),
-        }
-    } else {
-        None
+    let hashed_pin = match form.pin {
+        Some(pin_value) => Some(hash_credential(pin_value).await?),
+        None => None,
     };
 
     let profile_image_upload = form.profile_image_upload;
@@ -337,7 +539,7 @@ pub async fn create_user(
         username: next_username,
         password: hashed_password,
         pin: hashed_pin,
-        admin: existing_count == 0 || form.admin,
+        admin: form.admin,
         birthday: form
             .birthday
             .map(|value| value.trim().to_string())
@@ -349,18 +551,44 @@ pub async fn create_user(
         ),
     };
 
-    // Insert new user
-    let insert_result = db
-        .run(move |conn| diesel::insert_into(users).values(&user).execute(conn))
-        .await;
-    if insert_result.is_err() {
-        if let Some(uploaded_path) = next_profile_image_path.as_deref() {
-            let _ = remove_managed_profile_image(uploaded_path).await;
-        }
-        return Err(Status::InternalServerError);
+    // Re-check the user count inside the insert transaction so concurrent first-user
+    // requests cannot both skip authentication.
+    let authorized = auth_guard.is_some();
+    let insert_result = match db.get().await {
+        Some(conn) => conn
+            .run(move |conn| {
+                conn.immediate_transaction(|conn| {
+                    let current_count = users.count().get_result::<i64>(conn)?;
+                    if current_count > 0 && !authorized {
+                        return Ok(false);
+                    }
+                    let mut user = user;
+                    user.admin = current_count == 0 || user.admin;
+                    diesel::insert_into(users).values(&user).execute(conn)?;
+                    Ok::<_, diesel::result::Error>(true)
+                })
+            })
+            .await
+            .map_err(|_| Status::InternalServerError),
+        None => Err(Status::ServiceUnavailable),
+    };
+    let status = match insert_result {
+        Ok(true) => return Ok("User created"),
+        Ok(false) => Status::Unauthorized,
+        Err(status) => status,
+    };
+    if let Some(uploaded_path) = next_profile_image_path.as_deref() {
+        let _ = remove_managed_profile_image(uploaded_path).await;
     }
+    Err(status)
+}
 
-    Ok("User created")
+/// Hash a password or PIN with BCrypt on the blocking pool, keeping it off the async workers.
+async fn hash_credential(value: String) -> Result<String, Status> {
+    rocket::tokio::task::spawn_blocking(move || crate::auth::hash_password(&value))
+        .await
+        .map_err(|_| Status::InternalServerError)?
+        .map_err(|_| Status::InternalServerError)
 }
 
 #[get("/api/v1/user-profile-images/<filename>")]
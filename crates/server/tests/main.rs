@@ -1,4 +1,7 @@
 pub mod test_auth;
+pub mod test_certs;
+pub mod test_db;
+pub mod test_globals;
 pub mod test_media;
 pub mod test_metadata;
 #[cfg(feature = "tray")]
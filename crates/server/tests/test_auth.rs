@@ -1,5 +1,11 @@
 //! Authentication tests for the application.
 
+// standard imports
+use std::time::{
+    Duration as StdDuration,
+    Instant,
+};
+
 // lib imports
 use chrono::{
     Duration,
@@ -16,11 +22,24 @@ use serde_json::json;
 // local imports
 use koko::auth::{
     AdminGuard,
+    AdminStatusCache,
     AuthGuard,
+    JwtKeys,
+    LoginThrottle,
+    SUSPICIOUS_LOGIN_NOTIFICATIONS_PER_WINDOW,
+    SuspiciousLoginEvent,
+    SuspiciousLoginMonitor,
     UserGuard,
+    create_refresh_token,
     create_token,
+    create_token_with_keys,
+    create_token_with_ttl,
+    credential_fingerprint,
+    decode_refresh_token,
     decode_token,
+    decode_token_with_keys,
     hash_password,
+    hash_password_with_cost,
     verify_password,
 };
 
@@ -207,6 +226,8 @@ fn test_claims_struct_functionality() {
     let test_claims = Claims {
         sub: "test_user_123".to_string(),
         exp: (Utc::now() + Duration::hours(1)).timestamp() as usize,
+        token_type: Default::default(),
+        credential: None,
     };
 
     // Verify the claims data is accessible (same as what .claims() would return)
@@ -237,3 +258,281 @@ fn test_auth_guard_invalid_role_constant() {
         message
     );
 }
+
+#[test]
+fn test_admin_status_cache_hit() {
+    let cache = AdminStatusCache::new(StdDuration::from_secs(60));
+    assert_eq!(cache.get(1), None);
+
+    cache.insert(1, true);
+    cache.insert(2, false);
+
+    assert_eq!(cache.get(1), Some(true));
+    assert_eq!(cache.get(2), Some(false));
+}
+
+#[test]
+fn test_admin_status_cache_ttl_expiry() {
+    let ttl = StdDuration::from_secs(30);
+    let cache = AdminStatusCache::new(ttl);
+    let cached_at = Instant::now();
+    cache.insert_at(1, true, cached_at);
+
+    assert_eq!(
+        cache.get_at(1, cached_at + ttl - StdDuration::from_millis(1)),
+        Some(true)
+    );
+    assert_eq!(cache.get_at(1, cached_at + ttl), None);
+    // Expired entries are dropped, so a later lookup misses too.
+    assert_eq!(cache.get_at(1, cached_at), None);
+}
+
+#[test]
+fn test_admin_status_cache_invalidate() {
+    let cache = AdminStatusCache::new(StdDuration::from_secs(60));
+    cache.insert(1, true);
+    cache.insert(2, true);
+
+    cache.invalidate(1);
+
+    assert_eq!(cache.get(1), None);
+    assert_eq!(cache.get(2), Some(true));
+}
+
+const RS256_PRIVATE_KEY: &[u8] = include_bytes!("fixtures/jwt/rs256_private.pem");
+const RS256_PUBLIC_KEY: &[u8] = include_bytes!("fixtures/jwt/rs256_public.pem");
+const RS256_OTHER_PRIVATE_KEY: &[u8] = include_bytes!("fixtures/jwt/rs256_other_private.pem");
+const RS256_OTHER_PUBLIC_KEY: &[u8] = include_bytes!("fixtures/jwt/rs256_other_public.pem");
+
+#[test]
+fn test_rs256_token_creation_and_verification() {
+    let keys = JwtKeys::rsa_pem(RS256_PRIVATE_KEY, RS256_PUBLIC_KEY).expect("Should load keys");
+    assert_eq!(keys.algorithm(), jsonwebtoken::Algorithm::RS256);
+
+    let token = create_token_with_keys("42", &keys).expect("Should create token");
+    let header = jsonwebtoken::decode_header(&token).expect("Should decode header");
+    assert_eq!(header.alg, jsonwebtoken::Algorithm::RS256);
+
+    let claims = decode_token_with_keys(&token, &keys).expect("Should decode token");
+    assert_eq!(claims.sub, "42");
+}
+
+#[test]
+fn test_rs256_token_signed_with_wrong_key_is_rejected() {
+    let signing_keys = JwtKeys::rsa_pem(RS256_OTHER_PRIVATE_KEY, RS256_OTHER_PUBLIC_KEY)
+        .expect("Should load keys");
+    let verifying_keys =
+        JwtKeys::rsa_pem(RS256_PRIVATE_KEY, RS256_PUBLIC_KEY).expect("Should load keys");
+
+    let token = create_token_with_keys("42", &signing_keys).expect("Should create token");
+
+    assert!(decode_token_with_keys(&token, &verifying_keys).is_err());
+}
+
+#[test]
+fn test_rs256_verifier_rejects_hs256_token() {
+    let rsa_keys = JwtKeys::rsa_pem(RS256_PRIVATE_KEY, RS256_PUBLIC_KEY).expect("Should load keys");
+    let token = create_token("42", "test_secret_key_for_jwt").expect("Should create token");
+
+    assert!(decode_token_with_keys(&token, &rsa_keys).is_err());
+}
+
+#[test]
+fn test_hs256_token_signed_with_previous_secret_still_verifies() {
+    let keys = JwtKeys::hmac_with_previous("current_secret", &["previous_secret".to_string()]);
+
+    let old_token = create_token("42", "previous_secret").expect("Should create token");
+    let claims = decode_token_with_keys(&old_token, &keys).expect("Previous secret should verify");
+    assert_eq!(claims.sub, "42");
+
+    let retired_token = create_token("42", "retired_secret").expect("Should create token");
+    assert!(decode_token_with_keys(&retired_token, &keys).is_err());
+}
+
+#[test]
+fn test_hs256_new_tokens_use_current_secret() {
+    let keys = JwtKeys::hmac_with_previous("current_secret", &["previous_secret".to_string()]);
+
+    let token = create_token_with_keys("42", &keys).expect("Should create token");
+
+    assert!(decode_token(&token, "current_secret").is_ok());
+    assert!(decode_token(&token, "previous_secret").is_err());
+}
+
+#[test]
+fn test_token_ttl_sets_expiration() {
+    let keys = JwtKeys::hmac("test_secret_key_for_jwt");
+    let before = Utc::now().timestamp() as usize;
+
+    let token = create_token_with_ttl("42", &keys, StdDuration::from_secs(60))
+        .expect("Should create token");
+    let claims = decode_token_with_keys(&token, &keys).expect("Should decode token");
+
+    let after = Utc::now().timestamp() as usize;
+    assert!(claims.exp >= before + 60 && claims.exp <= after + 60);
+}
+
+#[test]
+fn test_suspicious_login_notifies_once_per_window() {
+    let monitor = SuspiciousLoginMonitor::default();
+    let window = StdDuration::from_secs(60);
+
+    let notifications: Vec<_> = (0..10)
+        .filter_map(|_| monitor.record_failure("alice", 3, window))
+        .collect();
+
+    assert_eq!(notifications, vec![3]);
+    assert_eq!(monitor.record_failure("bob", 3, window), None);
+}
+
+#[test]
+fn test_suspicious_login_window_and_success_reset() {
+    let monitor = SuspiciousLoginMonitor::default();
+    let window = StdDuration::from_millis(20);
+
+    assert_eq!(monitor.record_failure("alice", 2, window), None);
+    assert_eq!(monitor.record_failure("alice", 2, window), Some(2));
+    std::thread::sleep(StdDuration::from_millis(40));
+    assert_eq!(monitor.record_failure("alice", 2, window), None);
+    assert_eq!(monitor.record_failure("alice", 2, window), Some(2));
+
+    monitor.record_success("alice");
+    assert_eq!(monitor.record_failure("alice", 1, window), Some(1));
+    assert_eq!(monitor.record_failure("alice", 0, window), None);
+}
+
+#[test]
+fn test_suspicious_login_prunes_windows_and_limits_notifications() {
+    let monitor = SuspiciousLoginMonitor::default();
+    let window = StdDuration::from_secs(60);
+
+    let notified = (0..100)
+        .filter(|index| {
+            monitor
+                .record_failure(&format!("spray-{index}"), 1, window)
+                .is_some()
+        })
+        .count();
+    assert_eq!(notified, SUSPICIOUS_LOGIN_NOTIFICATIONS_PER_WINDOW as usize);
+
+    for index in 0..1000 {
+        monitor.record_failure(&format!("expired-{index}"), 1, StdDuration::ZERO);
+    }
+    assert!(monitor.tracked_usernames() < 1100);
+}
+
+#[test]
+fn test_suspicious_login_event_payload() {
+    let event = SuspiciousLoginEvent::new("alice", Some("192.0.2.7".parse().unwrap()), 5);
+
+    assert_eq!(
+        serde_json::to_value(&event).unwrap(),
+        json!({
+            "event": "suspicious_login",
+            "username": "alice",
+            "source_ip": "192.0.2.7",
+            "failed_attempts": 5
+        })
+    );
+}
+
+#[test]
+fn test_refresh_token_is_not_an_access_token() {
+    let keys = JwtKeys::hmac("test_secret_key_for_jwt");
+    let grace = StdDuration::from_secs(0);
+
+    let credential = credential_fingerprint("password_hash", None);
+    let refresh_token =
+        create_refresh_token("42", &credential, &keys).expect("Should create refresh token");
+    let claims = decode_refresh_token(&refresh_token, &keys, grace).expect("Should decode");
+    assert_eq!(claims.sub, "42");
+    assert_eq!(claims.credential, Some(credential));
+    assert!(decode_token_with_keys(&refresh_token, &keys).is_err());
+
+    let access_token = create_token_with_keys("42", &keys).expect("Should create token");
+    assert!(decode_refresh_token(&access_token, &keys, grace).is_err());
+}
+
+#[test]
+fn test_refresh_token_grace_window() {
+    let secret = "test_secret_key_for_jwt";
+    let keys = JwtKeys::hmac(secret);
+    let expired = encode(
+        &Header::default(),
+        &json!({
+            "sub": "42",
+            "exp": (Utc::now() - Duration::seconds(120)).timestamp(),
+            "token_type": "refresh"
+        }),
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+    .unwrap();
+
+    assert!(decode_refresh_token(&expired, &keys, StdDuration::from_secs(300)).is_ok());
+    assert!(decode_refresh_token(&expired, &keys, StdDuration::from_secs(30)).is_err());
+}
+
+#[test]
+fn test_login_throttle_locks_after_threshold() {
+    let throttle = LoginThrottle::default();
+    let lockout = StdDuration::from_secs(60);
+
+    throttle.record_failure("alice", 3, lockout);
+    throttle.record_failure("alice", 3, lockout);
+    assert!(!throttle.is_locked("alice"));
+    throttle.record_failure("alice", 3, lockout);
+    assert!(throttle.is_locked("alice"));
+    assert!(!throttle.is_locked("bob"));
+
+    throttle.record_failure("carol", 0, lockout);
+    assert!(!throttle.is_locked("carol"));
+}
+
+#[test]
+fn test_login_throttle_success_and_expiry_reset() {
+    let throttle = LoginThrottle::default();
+
+    throttle.record_failure("alice", 2, StdDuration::from_secs(60));
+    throttle.record_success("alice");
+    throttle.record_failure("alice", 2, StdDuration::from_secs(60));
+    assert!(!throttle.is_locked("alice"));
+
+    throttle.record_failure("alice", 2, StdDuration::from_millis(20));
+    assert!(throttle.is_locked("alice"));
+    std::thread::sleep(StdDuration::from_millis(40));
+    assert!(!throttle.is_locked("alice"));
+    throttle.record_failure("alice", 2, StdDuration::from_millis(20));
+    assert!(!throttle.is_locked("alice"));
+}
+
+#[test]
+fn test_login_throttle_prunes_expired_usernames() {
+    let throttle = LoginThrottle::default();
+    throttle.record_failure("alice", 1, StdDuration::from_secs(60));
+
+    for index in 0..1000 {
+        throttle.record_failure(&format!("spray-{index}"), 5, StdDuration::ZERO);
+    }
+
+    assert!(throttle.tracked_usernames() < 1000);
+    assert!(throttle.is_locked("alice"));
+}
+
+#[rstest]
+#[case(4, "$2b$04$")]
+#[case(5, "$2b$05$")]
+#[case(3, "$2b$12$")]
+#[case(32, "$2b$12$")]
+fn test_hash_password_with_cost(
+    #[case] cost: u32,
+    #[case] prefix: &str,
+) {
+    let hashed = hash_password_with_cost("password123", cost).expect("Should hash password");
+
+    assert!(
+        hashed.starts_with(prefix),
+        "{hashed} should start with {prefix}"
+    );
+    assert!(verify_password("password123", &hashed));
+    assert!(!verify_password("password124", &hashed));
+}
@@ -0,0 +1,97 @@
+//! Certificate tests for the application.
+
+// standard imports
+use std::fs;
+use std::path::PathBuf;
+
+// lib imports
+use rcgen::{
+    CertifiedKey,
+    generate_simple_self_signed,
+};
+
+// local imports
+use koko::certs::{
+    days_until_expiry,
+    inspect_certificate_pair,
+};
+
+fn write_certificate_pair(
+    name: &str,
+    mismatched_key: bool,
+) -> (String, String) {
+    let dir = PathBuf::from("./test_data").join("certs").join(name);
+    fs::create_dir_all(&dir).expect("Failed to create certificate test directory");
+
+    let CertifiedKey { cert, signing_key } = generate_simple_self_signed(vec![
+        "localhost".to_string(),
+        "127.0.0.1".to_string(),
+    ])
+    .expect("Failed to generate certificate");
+    let key_pem = if mismatched_key {
+        generate_simple_self_signed(vec!["localhost".to_string()])
+            .expect("Failed to generate second certificate")
+            .signing_key
+            .serialize_pem()
+    } else {
+        signing_key.serialize_pem()
+    };
+
+    let cert_path = dir.join("cert.pem");
+    let key_path = dir.join("key.pem");
+    fs::write(&cert_path, cert.pem()).expect("Failed to write certificate");
+    fs::write(&key_path, key_pem).expect("Failed to write private key");
+
+    (
+        cert_path.to_string_lossy().into_owned(),
+        key_path.to_string_lossy().into_owned(),
+    )
+}
+
+#[test]
+fn test_inspect_certificate_pair_accepts_matching_key() {
+    let (cert_path, key_path) = write_certificate_pair("matching", false);
+
+    let summary =
+        inspect_certificate_pair(&cert_path, &key_path).expect("Matching pair should be valid");
+
+    assert!(summary.subject.contains("rcgen self signed cert"));
+    assert_eq!(
+        summary.subject_alt_names,
+        vec![
+            "localhost".to_string(),
+            "127.0.0.1".to_string()
+        ]
+    );
+    assert!(summary.days_until_expiry > 0);
+    assert!(!summary.expired);
+}
+
+#[test]
+fn test_days_until_expiry() {
+    let day = 24 * 60 * 60;
+    let now = 1_700_000_000;
+
+    assert_eq!(days_until_expiry(now + 30 * day, now), 30);
+    assert_eq!(days_until_expiry(now + day - 1, now), 0);
+    assert_eq!(days_until_expiry(now, now), 0);
+    assert_eq!(days_until_expiry(now - 1, now), -1);
+}
+
+#[test]
+fn test_inspect_certificate_pair_rejects_mismatched_key() {
+    let (cert_path, key_path) = write_certificate_pair("mismatched", true);
+
+    let error = inspect_certificate_pair(&cert_path, &key_path)
+        .expect_err("Mismatched pair should be rejected");
+
+    assert!(error.contains("does not match"));
+}
+
+#[test]
+fn test_inspect_certificate_pair_reports_missing_files() {
+    let error = inspect_certificate_pair("./test_data/certs/missing.pem", "./missing-key.pem")
+        .expect_err("Missing certificate should be rejected");
+
+    assert!(error.contains("Failed to read certificate"));
+}
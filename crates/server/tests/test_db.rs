@@ -0,0 +1,85 @@
+//! Database startup tests for the application.
+
+// standard imports
+use std::fs;
+use std::path::PathBuf;
+
+// lib imports
+use diesel::Connection;
+use diesel::SqliteConnection;
+use diesel::connection::SimpleConnection;
+use rocket::error::ErrorKind;
+
+// local imports
+use koko::db::{
+    initialize_sqlite_database,
+    run_pending_sqlite_migrations,
+};
+use koko::signal_handler::ShutdownSignal;
+use koko::web;
+
+/// Create a database whose existing schema makes the initial migration fail.
+fn create_conflicting_database(name: &str) -> String {
+    let dir = PathBuf::from("./test_data").join("db").join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("Failed to create test database dir");
+    let db_path = dir.join("koko.db").to_string_lossy().to_string();
+
+    let mut conn = SqliteConnection::establish(&db_path).expect("Failed to open test database");
+    conn.batch_execute("CREATE TABLE media_libraries (id INTEGER PRIMARY KEY);")
+        .expect("Failed to create conflicting table");
+
+    db_path
+}
+
+#[test]
+fn test_failed_migration_error_names_revision() {
+    let db_path = create_conflicting_database("failed_migration_error");
+    let mut conn = SqliteConnection::establish(&db_path).expect("Failed to open test database");
+
+    let error = run_pending_sqlite_migrations(&mut conn)
+        .expect_err("Migration should fail against a conflicting schema")
+        .to_string();
+    assert!(
+        error.contains("a54d52c8da5e_initial_schema"),
+        "Expected the failing revision in the error, got {error}"
+    );
+    assert!(
+        error.contains("media_libraries"),
+        "Expected the underlying SQLite error, got {error}"
+    );
+
+    let error = initialize_sqlite_database(&db_path)
+        .expect_err("Database initialization should fail")
+        .to_string();
+    assert!(
+        error.contains("a54d52c8da5e_initial_schema"),
+        "Expected the failing revision in the error, got {error}"
+    );
+}
+
+#[rocket::async_test]
+async fn test_failed_migration_aborts_ignition() {
+    let db_path = create_conflicting_database("failed_migration_ignite");
+
+    let error = web::rocket_with_db_path(Some(db_path))
+        .ignite()
+        .await
+        .expect_err("Rocket should refuse to start when migrations fail");
+    assert!(matches!(error.kind(), ErrorKind::FailedFairings(_)));
+}
+
+#[rocket::async_test]
+async fn test_failed_migration_returns_launch_error_and_signals_shutdown() {
+    let db_path = create_conflicting_database("failed_migration_launch");
+    let shutdown_signal = ShutdownSignal::new();
+
+    let error = web::launch_rocket_with_shutdown(
+        web::rocket_with_db_path(Some(db_path)),
+        shutdown_signal.clone(),
+    )
+    .await
+    .expect_err("Launch should return the ignition error when migrations fail");
+    assert!(matches!(error.kind(), ErrorKind::FailedFairings(_)));
+    assert!(shutdown_signal.is_shutdown());
+}
@@ -0,0 +1,38 @@
+//! Tests for global helpers.
+
+// lib imports
+use rstest::rstest;
+
+// local imports
+use koko::globals::ServerUrl;
+
+#[rstest]
+#[case(false, "127.0.0.1", 9191, "http://127.0.0.1:9191")]
+#[case(true, "192.168.1.20", 443, "https://192.168.1.20:443")]
+#[case(true, "::1", 9191, "https://[::1]:9191")]
+#[case(false, "[fe80::1]", 8080, "http://[fe80::1]:8080")]
+#[case(true, "koko.local", 9191, "https://koko.local:9191")]
+#[case(false, " localhost ", 80, "http://localhost:80")]
+fn test_server_url_formats_hosts(
+    #[case] use_https: bool,
+    #[case] host: &str,
+    #[case] port: u16,
+    #[case] expected: &str,
+) {
+    let url = ServerUrl::new(use_https, host, port).expect("Server URL should be valid");
+    assert_eq!(url.to_string(), expected);
+}
+
+#[rstest]
+#[case("", 9191)]
+#[case("  ", 9191)]
+#[case("127.0.0.1", 0)]
+#[case("::1::2", 9191)]
+#[case("example.com/path", 9191)]
+#[case("user@example.com", 9191)]
+fn test_server_url_rejects_invalid_input(
+    #[case] host: &str,
+    #[case] port: u16,
+) {
+    assert!(ServerUrl::new(true, host, port).is_err());
+}
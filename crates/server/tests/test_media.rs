@@ -21,7 +21,10 @@ use koko::config::{
 };
 use koko::db::run_pending_sqlite_migrations;
 use koko::media::{
+    ClientProfile,
     LibraryScanStatus,
+    PlaybackDecision,
+    PlaybackSession,
     ShowMetadataDescendantPlan,
     ShowMetadataEpisodePlan,
     ShowMetadataSeasonPlan,
@@ -47,6 +50,7 @@ use koko::media::{
     list_media_item_children,
     list_media_items,
     mark_metadata_match_attempted,
+    prune_idle_playback_sessions,
     remove_library_setting,
     replace_library_settings,
     resolve_local_item_artwork_path,
@@ -306,7 +310,6 @@ fn test_inspect_transcoding_capability_reports_missing_binary() {
     let settings = FfmpegSettings {
         ffmpeg_path: "koko-ffmpeg-missing-binary".into(),
         ffprobe_path: "koko-ffprobe-missing-binary".into(),
-        ..FfmpegSettings::default()
     };
 
     let capability = inspect_transcoding_capability(&settings);
@@ -4188,3 +4191,63 @@ fn test_show_playback_target_resumes_in_progress_episode_per_user() {
     fs::remove_dir_all(root).unwrap();
     fs::remove_file(db_path).unwrap();
 }
+
+fn playback_session(
+    session_id: &str,
+    last_active_at: i64,
+) -> PlaybackSession {
+    PlaybackSession {
+        session_id: session_id.to_string(),
+        item_id: 1,
+        user_id: Some(1),
+        client_profile: ClientProfile {
+            client_type: "web".to_string(),
+            client_name: "Test".to_string(),
+            supported_containers: Vec::new(),
+            supported_video_codecs: Vec::new(),
+            supported_audio_codecs: Vec::new(),
+            supported_subtitle_formats: Vec::new(),
+            max_video_width: 0,
+            max_video_height: 0,
+            max_bitrate_kbps: 0,
+            supports_adaptive_streaming: false,
+            prefer_hls: false,
+        },
+        decision: PlaybackDecision {
+            item_id: 1,
+            can_direct_play: true,
+            transcode_required: false,
+            reason: "test".to_string(),
+            stream_url: None,
+            mime_type: None,
+            transcode_container: None,
+            transcode_video_codec: None,
+            transcode_audio_codec: None,
+            video_transcode_required: false,
+            audio_transcode_required: false,
+            source_video_codec: None,
+            source_audio_codec: None,
+            source_container: None,
+        },
+        created_at: 0,
+        last_active_at,
+        audio_stream_index: None,
+    }
+}
+
+#[test]
+fn test_prune_idle_playback_sessions_drops_only_idle_sessions() {
+    let now = 10_000;
+    let mut sessions = std::collections::HashMap::from([
+        ("idle".to_string(), playback_session("idle", now - 600)),
+        ("active".to_string(), playback_session("active", now - 599)),
+    ]);
+
+    assert!(prune_idle_playback_sessions(&mut sessions, now, 0).is_empty());
+    assert_eq!(sessions.len(), 2);
+
+    let pruned = prune_idle_playback_sessions(&mut sessions, now, 600);
+    assert_eq!(pruned, vec!["idle".to_string()]);
+    assert!(sessions.contains_key("active"));
+    assert!(!sessions.contains_key("idle"));
+}
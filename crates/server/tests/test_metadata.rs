@@ -6,6 +6,7 @@ use diesel::sql_types::Text;
 use koko::config::{
     DatabaseMaintenanceTaskSettings,
     FfmpegSettings,
+    JwtAlgorithm,
     MediaLibraryKind,
     MediaLibrarySettings,
     MetadataProviderId,
@@ -255,6 +256,7 @@ fn test_database_settings_round_trip_runtime_sections() {
         ffprobe_path: "C:/Tools/ffprobe.exe".into(),
     };
     settings.media.missing_item_auto_delete_days = Some(14);
+    settings.api.jwt_algorithm = JwtAlgorithm::Rs256;
     settings.scheduled_tasks = ScheduledTasksSettings {
         enabled: true,
         window: ScheduledTaskWindowSettings {
@@ -294,6 +296,7 @@ fn test_database_settings_round_trip_runtime_sections() {
     let loaded = load_database_settings(&mut conn, &Settings::default()).unwrap();
     assert_eq!(loaded.server.port, 8181);
     assert_eq!(loaded.ffmpeg.ffmpeg_path, "C:/Tools/ffmpeg.exe");
+    assert_eq!(loaded.api.jwt_algorithm, JwtAlgorithm::Rs256);
     assert_eq!(loaded.media.missing_item_auto_delete_days, None);
     assert_eq!(loaded.scheduled_tasks.window.start_time, "01:30");
     assert_eq!(
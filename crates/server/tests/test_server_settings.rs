@@ -0,0 +1,625 @@
+//! Tests for web server behavior driven by runtime settings.
+//!
+//! These tests replace the process-wide settings snapshot, so they live in their own test binary
+//! and run one at a time behind `SETTINGS_LOCK`.
+
+// standard imports
+use std::sync::{
+    Arc,
+    Mutex,
+};
+use std::time::Duration;
+
+// lib imports
+use diesel::Connection;
+use diesel::RunQueryDsl;
+use diesel::SqliteConnection;
+use diesel::connection::SimpleConnection;
+use once_cell::sync::Lazy;
+use rocket::fairing::AdHoc;
+use rocket::get;
+use rocket::http::{
+    ContentType,
+    Header,
+    Status,
+};
+use rocket::local::asynchronous::Client;
+use rocket::routes;
+use serde_json::{
+    Value,
+    json,
+};
+
+// local imports
+use koko::auth::{
+    create_token,
+    decode_token,
+};
+use koko::config::{
+    Settings,
+    current_settings,
+    replace_current_settings,
+};
+use koko::signal_handler::ShutdownSignal;
+use koko::web;
+
+#[derive(diesel::QueryableByName)]
+struct SettingValue {
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    value: String,
+}
+
+static SETTINGS_LOCK: Lazy<tokio::sync::Mutex<()>> = Lazy::new(|| tokio::sync::Mutex::new(()));
+
+struct TestServerStateGuard {
+    original_settings: Settings,
+    test_dir: std::path::PathBuf,
+}
+
+impl Drop for TestServerStateGuard {
+    fn drop(&mut self) {
+        replace_current_settings(self.original_settings.clone());
+        let _ = std::fs::remove_dir_all(&self.test_dir);
+    }
+}
+
+/// Install isolated settings adjusted by `configure`, returning a restore guard and a DB path.
+fn configure_isolated_settings(
+    name: &str,
+    configure: impl FnOnce(&mut Settings),
+) -> (TestServerStateGuard, String) {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let test_dir = std::env::temp_dir().join(format!(
+        "koko_server_settings_{}_{}_{}",
+        name,
+        std::process::id(),
+        timestamp
+    ));
+    let data_dir = test_dir.join("data");
+    std::fs::create_dir_all(&data_dir).expect("Failed to create isolated data dir");
+    std::env::set_var(
+        "KOKO_SETTINGS_PATH",
+        test_dir.join("settings.yml").to_string_lossy().to_string(),
+    );
+
+    let original_settings = current_settings();
+    let mut settings = original_settings.clone();
+    settings.general.data_dir = data_dir.to_string_lossy().to_string();
+    settings.server.port = 0;
+    settings.server.use_https = false;
+    configure(&mut settings);
+    replace_current_settings(settings);
+
+    let db_path = test_dir.join("koko.db").to_string_lossy().to_string();
+    (
+        TestServerStateGuard {
+            original_settings,
+            test_dir,
+        },
+        db_path,
+    )
+}
+
+#[rocket::async_test]
+async fn test_custom_keep_alive_is_applied_and_requests_succeed() {
+    let _lock = SETTINGS_LOCK.lock().await;
+    let (_guard, db_path) = configure_isolated_settings("keep_alive", |settings| {
+        settings.server.keep_alive_secs = 75;
+    });
+
+    let rocket = web::rocket_with_db_path(Some(db_path));
+    let keep_alive: u32 = rocket
+        .figment()
+        .extract_inner("keep_alive")
+        .expect("keep_alive should be configured");
+    assert_eq!(keep_alive, 75);
+
+    let client = Client::tracked(rocket)
+        .await
+        .expect("Rocket should ignite with a custom keep-alive");
+    let response = client.get("/logout").dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+}
+
+#[get("/slow")]
+async fn slow_route() -> &'static str {
+    rocket::tokio::time::sleep(Duration::from_millis(750)).await;
+    "done"
+}
+
+#[tokio::test]
+async fn test_in_flight_request_completes_during_shutdown() {
+    let _lock = SETTINGS_LOCK.lock().await;
+    let (_guard, db_path) = configure_isolated_settings("drain", |settings| {
+        settings.server.shutdown_grace_secs = 5;
+    });
+
+    let (port_tx, port_rx) = tokio::sync::oneshot::channel();
+    let port_notifier = Arc::new(Mutex::new(Some(port_tx)));
+    let rocket = web::rocket_with_db_path(Some(db_path))
+        .mount("/test", routes![slow_route])
+        .attach(AdHoc::on_liftoff("Report test port", move |rocket| {
+            let port = rocket.config().port;
+            let port_notifier = Arc::clone(&port_notifier);
+            Box::pin(async move {
+                if let Some(port_tx) = port_notifier.lock().unwrap().take() {
+                    let _ = port_tx.send(port);
+                }
+            })
+        }));
+
+    let shutdown_signal = ShutdownSignal::new();
+    let server_signal = shutdown_signal.clone();
+    let server_handle = tokio::spawn(async move {
+        web::launch_rocket_with_shutdown(rocket, server_signal)
+            .await
+            .expect("Web server should run without errors");
+    });
+
+    let port = tokio::time::timeout(Duration::from_secs(30), port_rx)
+        .await
+        .expect("Web server should launch within 30 seconds")
+        .expect("Web server task should not exit before launch");
+
+    let request = tokio::spawn(async move {
+        reqwest::get(format!("http://127.0.0.1:{}/test/slow", port))
+            .await
+            .expect("Slow request should get a response")
+    });
+
+    // Request shutdown while the slow request is still being handled.
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    shutdown_signal.shutdown();
+
+    let response = tokio::time::timeout(Duration::from_secs(10), request)
+        .await
+        .expect("Slow request should finish during the grace period")
+        .expect("Request task should not panic");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(response.text().await.unwrap(), "done");
+
+    tokio::time::timeout(Duration::from_secs(10), server_handle)
+        .await
+        .expect("Web server should stop after draining")
+        .expect("Web server task should not panic");
+}
+
+#[test]
+fn test_shutdown_timeout_covers_grace_and_mercy() {
+    let mut settings = Settings::default();
+    settings.server.shutdown_grace_secs = 10;
+
+    assert_eq!(
+        web::shutdown_timeout(&settings.server),
+        Duration::from_secs(u64::from(10 + web::SHUTDOWN_MERCY_SECS))
+    );
+}
+
+/// Create a user through the API and return a bearer token for it.
+async fn create_and_login(
+    client: &Client,
+    username: &str,
+    admin: bool,
+    admin_token: Option<&str>,
+) -> String {
+    let mut request = client.post("/create_user").header(ContentType::JSON).body(
+        json!({
+            "username": username,
+            "password": "password123",
+            "admin": admin
+        })
+        .to_string(),
+    );
+    if let Some(admin_token) = admin_token {
+        request = request.header(Header::new(
+            "Authorization",
+            format!("Bearer {}", admin_token),
+        ));
+    }
+    assert_eq!(request.dispatch().await.status(), Status::Ok);
+
+    let response = client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(json!({ "username": username, "password": "password123" }).to_string())
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    let body: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    body["token"].as_str().unwrap().to_string()
+}
+
+/// Insert a library with one item that has no backing file, which is enough for a session.
+fn insert_session_item(db_path: &str) -> i32 {
+    let mut conn = SqliteConnection::establish(db_path).expect("Failed to open test database");
+    conn.batch_execute(
+        "INSERT INTO media_libraries (id, name, path, kind) VALUES (1, 'Movies', '/movies', \
+         'movies'); INSERT INTO media_items (id, library_id, identity_key, item_type, \
+         display_title) VALUES (1, 1, 'movie:quota', 'movie', 'Quota Movie');",
+    )
+    .expect("Failed to insert session test item");
+    1
+}
+
+async fn create_session(
+    client: &Client,
+    token: &str,
+    item_id: i32,
+) -> (Status, Option<String>) {
+    let response = client
+        .post("/api/v1/sessions")
+        .header(ContentType::JSON)
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .body(
+            json!({
+                "item_id": item_id,
+                "client_profile": {
+                    "client_type": "web",
+                    "client_name": "Quota Test",
+                    "supported_containers": [],
+                    "supported_video_codecs": [],
+                    "supported_audio_codecs": [],
+                    "supported_subtitle_formats": [],
+                    "max_video_width": 0,
+                    "max_video_height": 0,
+                    "max_bitrate_kbps": 0,
+                    "supports_adaptive_streaming": false,
+                    "prefer_hls": false
+                }
+            })
+            .to_string(),
+        )
+        .dispatch()
+        .await;
+    let status = response.status();
+    let session_id = response
+        .into_string()
+        .await
+        .and_then(|body| serde_json::from_str::<Value>(&body).ok())
+        .and_then(|body| body["session_id"].as_str().map(str::to_string));
+    (status, session_id)
+}
+
+#[rocket::async_test]
+async fn test_playback_session_quota_per_user() {
+    let _lock = SETTINGS_LOCK.lock().await;
+    let (_guard, db_path) = configure_isolated_settings("session_quota", |settings| {
+        settings.server.max_playback_sessions_per_user = 2;
+    });
+
+    let client = Client::tracked(web::rocket_with_db_path(Some(db_path.clone())))
+        .await
+        .expect("Rocket should ignite with a session quota");
+    let admin_token = create_and_login(&client, "admin", true, None).await;
+    let viewer_token = create_and_login(&client, "viewer", false, Some(&admin_token)).await;
+    let item_id = insert_session_item(&db_path);
+
+    let (status, first_session) = create_session(&client, &viewer_token, item_id).await;
+    assert_eq!(status, Status::Ok);
+    let (status, _) = create_session(&client, &viewer_token, item_id).await;
+    assert_eq!(status, Status::Ok);
+    let (status, _) = create_session(&client, &viewer_token, item_id).await;
+    assert_eq!(status, Status::TooManyRequests);
+
+    // Admins are exempt from the limit.
+    for _ in 0..3 {
+        let (status, _) = create_session(&client, &admin_token, item_id).await;
+        assert_eq!(status, Status::Ok);
+    }
+
+    // Ending a session frees a slot for the same user.
+    let response = client
+        .delete(format!("/api/v1/sessions/{}", first_session.unwrap()))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::NoContent);
+    let (status, _) = create_session(&client, &viewer_token, item_id).await;
+    assert_eq!(status, Status::Ok);
+}
+
+#[rocket::async_test]
+async fn test_username_charset_policy() {
+    let _lock = SETTINGS_LOCK.lock().await;
+    let (_guard, db_path) = configure_isolated_settings("username_charset", |settings| {
+        settings.api.username_restrict_charset = true;
+    });
+
+    let client = Client::tracked(web::rocket_with_db_path(Some(db_path)))
+        .await
+        .expect("Rocket should ignite with a username policy");
+    let response = client
+        .post("/create_user")
+        .header(ContentType::JSON)
+        .body(
+            json!({ "username": "bad name!", "password": "password123", "admin": true })
+                .to_string(),
+        )
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::BadRequest);
+
+    create_and_login(&client, "good.name_1", true, None).await;
+}
+
+#[rocket::async_test]
+async fn test_settings_update_rejects_inverted_username_length_bounds() {
+    let _lock = SETTINGS_LOCK.lock().await;
+    let (_guard, db_path) = configure_isolated_settings("username_length_bounds", |_| {});
+
+    let client = Client::tracked(web::rocket_with_db_path(Some(db_path)))
+        .await
+        .expect("Rocket should ignite with default settings");
+    let token = create_and_login(&client, "admin", true, None).await;
+    let response = client.get("/api/v1/settings").dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    let body: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    let mut settings = body["settings"].clone();
+    settings["api"]["username_min_length"] = json!(10);
+    settings["api"]["username_max_length"] = json!(5);
+
+    let response = client
+        .put("/api/v1/settings")
+        .header(ContentType::JSON)
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .body(settings.to_string())
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::BadRequest);
+
+    let api = current_settings().api;
+    assert!(api.username_min_length <= api.username_max_length);
+}
+
+#[rocket::async_test]
+async fn test_configured_jwt_secret_signs_tokens_and_is_redacted() {
+    let _lock = SETTINGS_LOCK.lock().await;
+    std::env::set_var("KOKO_SECRET_STORE", "sample");
+    let (_guard, db_path) = configure_isolated_settings("jwt_secret", |settings| {
+        settings.api.jwt_secret = Some("configured_secret".to_string());
+        settings.api.jwt_previous_secrets = vec!["previous_secret".to_string()];
+    });
+
+    let client = Client::tracked(web::rocket_with_db_path(Some(db_path.clone())))
+        .await
+        .expect("Rocket should ignite with a configured JWT secret");
+    let token = create_and_login(&client, "admin", true, None).await;
+    assert!(decode_token(&token, "configured_secret").is_ok());
+
+    let old_token = create_token("1", "previous_secret").unwrap();
+    let response = client
+        .get("/jwt_test")
+        .header(Header::new(
+            "Authorization",
+            format!("Bearer {}", old_token),
+        ))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+
+    let response = client.get("/api/v1/settings").dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    let body: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    let api = &body["settings"]["api"];
+    assert_eq!(api["jwt_algorithm"], "HS256");
+    assert_eq!(api["jwt_secret"], Value::Null);
+    assert_eq!(api["jwt_previous_secrets"], Value::Null);
+    assert_eq!(api["jwt_secret_ref"], Value::Null);
+
+    let mut conn = SqliteConnection::establish(&db_path).expect("Failed to open test database");
+    let api_row = diesel::sql_query("SELECT value FROM app_settings WHERE key = 'api'")
+        .get_result::<SettingValue>(&mut conn)
+        .unwrap();
+    assert!(!api_row.value.contains("\"configured_secret\""));
+    assert!(!api_row.value.contains("\"previous_secret\""));
+    assert!(api_row.value.contains("jwt_secret_ref"));
+}
+
+/// Accept a single HTTP request and return its body.
+async fn capture_webhook_body(listener: tokio::net::TcpListener) -> String {
+    use tokio::io::{
+        AsyncReadExt,
+        AsyncWriteExt,
+    };
+
+    let (mut stream, _) = listener.accept().await.unwrap();
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+    loop {
+        let read = stream.read(&mut buffer).await.unwrap();
+        request.extend_from_slice(&buffer[..read]);
+        let text = String::from_utf8_lossy(&request).to_string();
+        if let Some((headers, body)) = text.split_once("\r\n\r\n") {
+            let content_length = headers
+                .lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("content-length")
+                        .then(|| value.trim().parse::<usize>().ok())?
+                })
+                .unwrap_or(0);
+            if body.len() >= content_length {
+                stream
+                    .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")
+                    .await
+                    .unwrap();
+                return body.to_string();
+            }
+        }
+        assert!(
+            read > 0,
+            "webhook connection closed before the body arrived"
+        );
+    }
+}
+
+#[rocket::async_test]
+async fn test_suspicious_login_calls_webhook_once() {
+    let _lock = SETTINGS_LOCK.lock().await;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let webhook_url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let (_guard, db_path) = configure_isolated_settings("suspicious_login", |settings| {
+        settings.api.suspicious_login_threshold = 3;
+        settings.api.suspicious_login_webhook_url = Some(webhook_url);
+    });
+
+    let client = Client::tracked(web::rocket_with_db_path(Some(db_path)))
+        .await
+        .expect("Rocket should ignite with a suspicious login webhook");
+    create_and_login(&client, "alice", true, None).await;
+
+    let webhook = tokio::spawn(capture_webhook_body(listener));
+    for _ in 0..5 {
+        let response = client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(json!({ "username": "alice", "password": "wrong" }).to_string())
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::Unauthorized);
+    }
+
+    let body = tokio::time::timeout(Duration::from_secs(10), webhook)
+        .await
+        .expect("webhook should be called")
+        .unwrap();
+    let payload: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(payload["event"], "suspicious_login");
+    assert_eq!(payload["username"], "alice");
+    assert_eq!(payload["failed_attempts"], 3);
+    assert!(payload.get("source_ip").is_some());
+}
+
+/// Attempt a password login and return the response status.
+async fn login_status(
+    client: &Client,
+    username: &str,
+    password: &str,
+) -> Status {
+    client
+        .post("/login")
+        .header(ContentType::JSON)
+        .body(json!({ "username": username, "password": password }).to_string())
+        .dispatch()
+        .await
+        .status()
+}
+
+#[rocket::async_test]
+async fn test_login_lockout_after_failed_attempts() {
+    let _lock = SETTINGS_LOCK.lock().await;
+    let (_guard, db_path) = configure_isolated_settings("login_lockout", |settings| {
+        settings.api.login_lockout_threshold = 3;
+        settings.api.login_lockout_secs = 300;
+    });
+
+    let client = Client::tracked(web::rocket_with_db_path(Some(db_path)))
+        .await
+        .expect("Rocket should ignite with login lockout");
+    create_and_login(&client, "alice", true, None).await;
+
+    // A successful login clears earlier failures.
+    for _ in 0..2 {
+        assert_eq!(
+            login_status(&client, "alice", "wrong").await,
+            Status::Unauthorized
+        );
+    }
+    assert_eq!(
+        login_status(&client, "alice", "password123").await,
+        Status::Ok
+    );
+
+    for _ in 0..3 {
+        assert_eq!(
+            login_status(&client, "alice", "wrong").await,
+            Status::Unauthorized
+        );
+    }
+    assert_eq!(
+        login_status(&client, "alice", "password123").await,
+        Status::TooManyRequests
+    );
+}
+
+#[rocket::async_test]
+async fn test_cors_headers_for_allowed_origins() {
+    let _lock = SETTINGS_LOCK.lock().await;
+    let (_guard, db_path) = configure_isolated_settings("cors", |settings| {
+        settings.server.cors_origins = vec!["https://viewer.example".to_string()];
+    });
+
+    let client = Client::tracked(web::rocket_with_db_path(Some(db_path)))
+        .await
+        .expect("Rocket should ignite with CORS origins");
+
+    let response = client
+        .get("/logout")
+        .header(Header::new("Origin", "https://viewer.example"))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        response.headers().get_one("Access-Control-Allow-Origin"),
+        Some("https://viewer.example")
+    );
+
+    let response = client
+        .get("/logout")
+        .header(Header::new("Origin", "https://other.example"))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        response.headers().get_one("Access-Control-Allow-Origin"),
+        None
+    );
+    assert_eq!(response.headers().get_one("Vary"), Some("Origin"));
+
+    let response = client.get("/logout").dispatch().await;
+    assert_eq!(response.headers().get_one("Vary"), Some("Origin"));
+
+    let response = client
+        .options("/login")
+        .header(Header::new("Origin", "https://viewer.example"))
+        .header(Header::new("Access-Control-Request-Method", "POST"))
+        .header(Header::new(
+            "Access-Control-Request-Headers",
+            "content-type",
+        ))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::NoContent);
+    assert_eq!(
+        response.headers().get_one("Access-Control-Allow-Origin"),
+        Some("https://viewer.example")
+    );
+    assert!(
+        response
+            .headers()
+            .get_one("Access-Control-Allow-Methods")
+            .is_some_and(|methods| methods.contains("POST"))
+    );
+}
+
+#[rocket::async_test]
+async fn test_cors_disabled_by_default() {
+    let _lock = SETTINGS_LOCK.lock().await;
+    let (_guard, db_path) = configure_isolated_settings("cors_disabled", |_| {});
+
+    let client = Client::tracked(web::rocket_with_db_path(Some(db_path)))
+        .await
+        .expect("Rocket should ignite without CORS origins");
+    let response = client
+        .get("/logout")
+        .header(Header::new("Origin", "https://viewer.example"))
+        .dispatch()
+        .await;
+    assert_eq!(
+        response.headers().get_one("Access-Control-Allow-Origin"),
+        None
+    );
+    assert_eq!(response.headers().get_one("Vary"), None);
+}
@@ -24,7 +24,9 @@ use koko::config::{
 };
 use koko::signal_handler::{
     ShutdownCoordinator,
+    ShutdownReason,
     ShutdownSignal,
+    run_idle_watchdog,
 };
 use koko::web;
 
@@ -100,6 +102,27 @@ mod shutdown_signal {
         assert!(signal.is_shutdown());
     }
 
+    #[test]
+    fn reason_defaults_to_requested() {
+        let signal = ShutdownSignal::new();
+        assert_eq!(signal.reason(), None);
+
+        signal.shutdown();
+        assert_eq!(signal.reason(), Some(ShutdownReason::Requested));
+    }
+
+    #[test]
+    fn first_reason_is_kept() {
+        let signal = ShutdownSignal::new();
+        let cloned = signal.clone();
+
+        cloned.shutdown_with_reason(ShutdownReason::Restart);
+        signal.shutdown();
+
+        assert!(signal.is_shutdown());
+        assert_eq!(signal.reason(), Some(ShutdownReason::Restart));
+    }
+
     #[test]
     fn cloning() {
         let original = ShutdownSignal::new();
@@ -365,6 +388,156 @@ mod shutdown_signal {
         );
         handle.join().unwrap();
     }
+
+    #[test]
+    fn wait_wakes_every_waiter_promptly() {
+        let signal = ShutdownSignal::new();
+        let (completed_tx, completed_rx) = std::sync::mpsc::channel();
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let signal_clone = signal.clone();
+                let completed_tx = completed_tx.clone();
+                thread::spawn(move || {
+                    signal_clone.wait();
+                    completed_tx
+                        .send(std::time::Instant::now())
+                        .expect("Should notify that wait completed");
+                })
+            })
+            .collect();
+
+        thread::sleep(Duration::from_millis(50));
+        let shutdown_at = std::time::Instant::now();
+        signal.shutdown();
+
+        for _ in 0..4 {
+            let woke_at = completed_rx
+                .recv_timeout(Duration::from_secs(5))
+                .expect("Every waiter should return after shutdown");
+            assert!(
+                woke_at.duration_since(shutdown_at) < Duration::from_millis(50),
+                "Waiters should wake without polling delay"
+            );
+        }
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+}
+
+mod wait_async {
+    use super::*;
+
+    #[tokio::test]
+    async fn completes_promptly_after_shutdown() {
+        let signal = ShutdownSignal::new();
+        let signal_clone = signal.clone();
+        let waiter = tokio::spawn(async move { signal_clone.wait_async().await });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(
+            !waiter.is_finished(),
+            "wait_async should block until shutdown"
+        );
+
+        signal.shutdown();
+        signal.shutdown();
+        timeout(Duration::from_millis(50), waiter)
+            .await
+            .expect("wait_async should complete promptly after shutdown")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn returns_immediately_when_already_shutdown() {
+        let signal = ShutdownSignal::new();
+        signal.shutdown_with_reason(ShutdownReason::Restart);
+
+        timeout(Duration::from_millis(50), signal.wait_async())
+            .await
+            .expect("wait_async should return for an already shutdown signal");
+        assert_eq!(signal.reason(), Some(ShutdownReason::Restart));
+    }
+
+    #[test]
+    fn completes_when_shutdown_from_another_thread() {
+        let signal = ShutdownSignal::new();
+        let signal_clone = signal.clone();
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            signal_clone.shutdown();
+        });
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            timeout(Duration::from_secs(5), signal.wait_async())
+                .await
+                .expect("wait_async should observe a shutdown from another thread");
+        });
+        handle.join().unwrap();
+    }
+}
+
+mod idle_watchdog {
+    use super::*;
+
+    #[test]
+    fn fires_after_idle_timeout_with_no_clients() {
+        let signal = ShutdownSignal::new();
+        let started_at = std::time::Instant::now();
+
+        run_idle_watchdog(
+            &signal,
+            Duration::from_millis(100),
+            Duration::from_millis(10),
+            || 0,
+        );
+
+        assert!(started_at.elapsed() >= Duration::from_millis(100));
+        assert!(signal.is_shutdown());
+        assert_eq!(signal.reason(), Some(ShutdownReason::IdleTimeout));
+    }
+
+    #[test]
+    fn active_clients_reset_the_idle_timer() {
+        let signal = ShutdownSignal::new();
+        let active = Arc::new(AtomicU32::new(1));
+
+        let watchdog_signal = signal.clone();
+        let watchdog_active = active.clone();
+        let handle = thread::spawn(move || {
+            run_idle_watchdog(
+                &watchdog_signal,
+                Duration::from_millis(100),
+                Duration::from_millis(10),
+                || watchdog_active.load(Ordering::SeqCst) as usize,
+            );
+        });
+
+        thread::sleep(Duration::from_millis(250));
+        assert!(
+            !signal.is_shutdown(),
+            "Watchdog should not fire with clients"
+        );
+
+        active.store(0, Ordering::SeqCst);
+        handle.join().unwrap();
+        assert_eq!(signal.reason(), Some(ShutdownReason::IdleTimeout));
+    }
+
+    #[test]
+    fn returns_when_shut_down_elsewhere() {
+        let signal = ShutdownSignal::new();
+        signal.shutdown();
+
+        run_idle_watchdog(&signal, Duration::ZERO, Duration::from_millis(10), || 0);
+
+        assert_eq!(signal.reason(), Some(ShutdownReason::Requested));
+    }
 }
 
 mod managed_thread {
@@ -508,8 +681,12 @@ mod shutdown_coordinator {
             thread::sleep(Duration::from_millis(20));
         });
 
-        // This should handle the panic gracefully and log warnings
-        coordinator.wait_for_completion();
+        // This should handle the panic gracefully and report the failed thread
+        let report = coordinator.wait_for_completion();
+        assert_eq!(report.total, 2);
+        assert_eq!(report.succeeded, 1);
+        assert_eq!(report.failed, vec!["panic-thread".to_string()]);
+        assert!(!report.is_success());
     }
 
     #[test]
@@ -527,9 +704,51 @@ mod shutdown_coordinator {
         }
 
         // This should complete successfully and log success message
-        coordinator.wait_for_completion();
+        let report = coordinator.wait_for_completion();
 
         assert_eq!(counter.load(Ordering::Relaxed), 3);
+        assert_eq!(report.total, 3);
+        assert_eq!(report.succeeded, 3);
+        assert!(report.is_success());
+    }
+
+    #[test]
+    fn restartable_thread_recovers_from_panics() {
+        let mut coordinator = create_test_coordinator();
+        let main_signal = coordinator.signal();
+        let attempts = Arc::new(AtomicU32::new(0));
+
+        let attempts_clone = Arc::clone(&attempts);
+        coordinator.register_restartable_thread("flaky-thread", 2, move |_| {
+            if attempts_clone.fetch_add(1, Ordering::SeqCst) < 2 {
+                panic!("Intentional panic for testing");
+            }
+        });
+
+        let report = coordinator.wait_for_completion();
+
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert!(report.is_success());
+        assert!(!main_signal.is_shutdown());
+    }
+
+    #[test]
+    fn restartable_thread_shuts_down_after_retry_limit() {
+        let mut coordinator = create_test_coordinator();
+        let main_signal = coordinator.signal();
+        let attempts = Arc::new(AtomicU32::new(0));
+
+        let attempts_clone = Arc::clone(&attempts);
+        coordinator.register_restartable_thread("broken-thread", 1, move |_| {
+            attempts_clone.fetch_add(1, Ordering::SeqCst);
+            panic!("Intentional panic for testing");
+        });
+
+        let report = coordinator.wait_for_completion();
+
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert_eq!(report.failed, vec!["broken-thread".to_string()]);
+        assert!(main_signal.is_shutdown());
     }
 
     #[test]
@@ -569,6 +788,23 @@ mod shutdown_coordinator {
         assert!(long_running_shutdown.load(Ordering::Relaxed));
     }
 
+    #[test]
+    fn monitor_propagates_restart_reason() {
+        let mut coordinator = create_test_coordinator();
+        let main_signal = coordinator.signal();
+
+        coordinator.register_thread("restarting-thread", move |shutdown_signal| {
+            thread::sleep(Duration::from_millis(50));
+            shutdown_signal.shutdown_with_reason(ShutdownReason::Restart);
+        });
+
+        coordinator.start_monitor();
+        coordinator.wait_for_completion();
+
+        assert!(main_signal.is_shutdown());
+        assert_eq!(main_signal.reason(), Some(ShutdownReason::Restart));
+    }
+
     #[test]
     fn monitor_external_shutdown_signal() {
         let mut coordinator = create_test_coordinator();
@@ -714,7 +950,9 @@ mod integration {
 
         // Start web server in background
         let web_handle = tokio::spawn(async move {
-            web::launch_rocket_with_shutdown(rocket, shutdown_signal_clone).await;
+            web::launch_rocket_with_shutdown(rocket, shutdown_signal_clone)
+                .await
+                .expect("Web server should run without errors");
         });
 
         timeout(Duration::from_secs(30), launched_rx)
@@ -1,10 +1,16 @@
 //! Shared test utilities to eliminate code duplication across test files.
 
 // standard imports
+use std::path::Path;
+use std::sync::Once;
 use std::sync::atomic::{
     AtomicU64,
     Ordering,
 };
+use std::time::{
+    Duration,
+    SystemTime,
+};
 
 // lib imports
 use once_cell::sync::Lazy;
@@ -21,8 +27,58 @@ use koko::web;
 
 // Global counter to ensure unique database files across all tests
 static GLOBAL_TEST_COUNTER: AtomicU64 = AtomicU64::new(0);
-static TEST_CLIENT_CREATION_LOCK: Lazy<std::sync::Mutex<()>> =
-    Lazy::new(|| std::sync::Mutex::new(()));
+static TEST_CLIENT_CREATION_LOCK: Lazy<tokio::sync::Mutex<()>> =
+    Lazy::new(|| tokio::sync::Mutex::new(()));
+static TEST_DATA_PRUNE: Once = Once::new();
+
+/// Test databases and settings files older than this are left over from an earlier run.
+pub const STALE_TEST_DATA_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// Whether a file in `./test_data` is a leftover database or settings file from an earlier run.
+pub fn is_stale_test_data_file(
+    path: &Path,
+    modified: SystemTime,
+    now: SystemTime,
+    max_age: Duration,
+) -> bool {
+    let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let is_test_data = [
+        ".db",
+        ".db-wal",
+        ".db-shm",
+        ".db-journal",
+        ".yml",
+    ]
+    .iter()
+    .any(|suffix| file_name.ends_with(suffix));
+
+    is_test_data && now.duration_since(modified).is_ok_and(|age| age > max_age)
+}
+
+/// Remove stale test databases and settings files left behind by panicked or aborted runs.
+pub fn prune_stale_test_data(
+    dir: &Path,
+    max_age: Duration,
+) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let now = SystemTime::now();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let Ok(modified) = metadata.modified() else {
+            continue;
+        };
+        if metadata.is_file() && is_stale_test_data_file(&path, modified, now, max_age) {
+            std::fs::remove_file(&path).ok();
+        }
+    }
+}
 
 /// Enhanced test response structure with headers
 pub struct TestResponse {
@@ -33,7 +89,7 @@ pub struct TestResponse {
 
 /// Create a test client with an isolated database
 pub async fn create_test_client(prefix: Option<&str>) -> Client {
-    let _lock = TEST_CLIENT_CREATION_LOCK.lock().unwrap();
+    let _lock = TEST_CLIENT_CREATION_LOCK.lock().await;
 
     // Set the test environment first
     use koko::globals::CURRENT_ENV;
@@ -52,6 +108,9 @@ pub async fn create_test_client(prefix: Option<&str>) -> Client {
 
     // Ensure test_data directory exists
     std::fs::create_dir_all("./test_data").expect("Failed to create test_data directory");
+    TEST_DATA_PRUNE.call_once(|| {
+        prune_stale_test_data(Path::new("./test_data"), STALE_TEST_DATA_AGE);
+    });
 
     // Create the full database path
     let db_path = format!("./test_data/{}", db_name);
@@ -243,3 +302,71 @@ pub async fn create_and_login_user(
 
     login_user(client, username, password, Some(Status::Ok)).await
 }
+
+#[test]
+fn test_stale_test_data_predicate() {
+    let now = SystemTime::now();
+    let old = now - Duration::from_secs(3600);
+    let recent = now - Duration::from_secs(5);
+
+    assert!(is_stale_test_data_file(
+        Path::new("./test_data/request_1_123.db"),
+        old,
+        now,
+        STALE_TEST_DATA_AGE
+    ));
+    assert!(is_stale_test_data_file(
+        Path::new("./test_data/request_1_123.db-wal"),
+        old,
+        now,
+        STALE_TEST_DATA_AGE
+    ));
+    assert!(is_stale_test_data_file(
+        Path::new("./test_data/request_1_123.yml"),
+        old,
+        now,
+        STALE_TEST_DATA_AGE
+    ));
+    assert!(!is_stale_test_data_file(
+        Path::new("./test_data/request_1_123.db"),
+        recent,
+        now,
+        STALE_TEST_DATA_AGE
+    ));
+    assert!(!is_stale_test_data_file(
+        Path::new("./test_data/koko.log"),
+        old,
+        now,
+        STALE_TEST_DATA_AGE
+    ));
+    // A modification time in the future is never treated as stale.
+    assert!(!is_stale_test_data_file(
+        Path::new("./test_data/request_1_123.db"),
+        now + Duration::from_secs(60),
+        now,
+        STALE_TEST_DATA_AGE
+    ));
+}
+
+#[test]
+fn test_prune_stale_test_data_keeps_fresh_files() {
+    let dir = std::env::temp_dir().join(format!(
+        "koko_prune_test_data_{}_{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    let fresh = dir.join("fresh_1_1.db");
+    std::fs::write(&fresh, b"").unwrap();
+
+    prune_stale_test_data(&dir, STALE_TEST_DATA_AGE);
+    assert!(fresh.exists());
+
+    prune_stale_test_data(&dir, Duration::ZERO);
+    assert!(!fresh.exists());
+
+    std::fs::remove_dir_all(&dir).ok();
+}
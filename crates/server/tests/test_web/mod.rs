@@ -6,10 +6,17 @@ mod test_auth_routes;
 use rocket::http::Status;
 
 // local imports
+use koko::media::{
+    BinaryCapability,
+    TranscodingCapability,
+};
 use koko::web;
 
 // test imports
-use crate::test_utils::make_request;
+use crate::test_utils::{
+    create_test_client,
+    make_request,
+};
 
 #[rocket::async_test]
 async fn test_swagger_ui_route() {
@@ -39,6 +46,32 @@ async fn test_rapidoc_route() {
     .await;
 }
 
+#[rocket::async_test]
+async fn test_openapi_spec_includes_server_url() {
+    let client = create_test_client(Some("openapi_spec")).await;
+
+    let response = make_request(
+        Some(&client),
+        "get",
+        "/openapi.json",
+        None,
+        None,
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    let spec: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+
+    let servers = spec["servers"]
+        .as_array()
+        .expect("Spec should list servers");
+    assert_eq!(servers.len(), 1);
+    // A relative server resolves against the host that served the spec, unlike the bind
+    // address, which may be `0.0.0.0` or hidden behind a proxy.
+    assert_eq!(servers[0]["url"], "./");
+    assert!(spec["paths"]["/api/v1/admin/restart"].is_object());
+}
+
 #[rocket::async_test]
 async fn test_non_existent_route() {
     make_request(
@@ -73,3 +106,76 @@ async fn test_web_server_with_custom_db_path() {
         "Rocket with custom DB path should ignite successfully"
     );
 }
+
+#[tokio::test]
+async fn test_active_client_count_does_not_block_inside_the_runtime() {
+    // The idle watchdog's probe must not use blocking locks, which panic inside a runtime.
+    let _ = web::active_client_count();
+}
+
+#[test]
+fn test_mounted_api_versions() {
+    let versions = web::mounted_api_versions([
+        "/api/v2/users",
+        "/api/v1/users",
+        "/api/v1/settings",
+        "/api/vnext/users",
+        "/openapi.json",
+        "/",
+    ]);
+
+    assert_eq!(
+        versions,
+        vec![
+            "v1".to_string(),
+            "v2".to_string()
+        ]
+    );
+}
+
+#[rocket::async_test]
+async fn test_mounted_api_versions_match_the_rocket_routes() {
+    let rocket = web::rocket();
+
+    assert_eq!(
+        web::mounted_api_versions(rocket.routes().map(|route| route.uri.path())),
+        vec!["v1".to_string()]
+    );
+}
+
+#[test]
+fn test_startup_summary_formatting() {
+    let transcoding = TranscodingCapability {
+        ffmpeg: BinaryCapability {
+            configured_path: "ffmpeg".to_string(),
+            available: true,
+            version: Some("ffmpeg version 7.1".to_string()),
+            error: None,
+        },
+        ffprobe: BinaryCapability {
+            configured_path: "/opt/ffprobe".to_string(),
+            available: false,
+            version: None,
+            error: Some("No such file or directory".to_string()),
+        },
+    };
+
+    let summary = web::startup_summary(
+        "0.0.0.0".parse().unwrap(),
+        9191,
+        true,
+        "sqlite:///data/koko.db",
+        &["v1".to_string()],
+        &transcoding,
+    );
+
+    assert_eq!(
+        summary,
+        format!(
+            "Koko {} startup summary\n  listen: 0.0.0.0:9191\n  tls: enabled\n  database: \
+             sqlite:///data/koko.db\n  api versions: v1\n  ffmpeg: available (ffmpeg version \
+             7.1)\n  ffprobe: unavailable (/opt/ffprobe: No such file or directory)",
+            env!("CARGO_PKG_VERSION")
+        )
+    );
+}
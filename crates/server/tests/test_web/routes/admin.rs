@@ -0,0 +1,310 @@
+// lib imports
+use rocket::http::Status;
+use rocket::serde::json::{
+    Value,
+    json,
+    serde_json,
+};
+
+// local imports
+use koko::signal_handler::{
+    ShutdownReason,
+    ShutdownSignal,
+};
+
+// test imports
+use crate::test_utils::{
+    create_and_login_user,
+    create_test_client,
+    login_user,
+    make_request,
+};
+
+#[rocket::async_test]
+async fn test_restart_requires_confirmation_token_and_signals_restart() {
+    let client = create_test_client(Some("admin_route_restart")).await;
+    let shutdown_signal = client
+        .rocket()
+        .state::<ShutdownSignal>()
+        .cloned()
+        .expect("Web server should manage a shutdown signal");
+    let token = create_and_login_user(&client, "admin", "password123", true, None)
+        .await
+        .expect("Expected admin to be able to log in");
+    let auth_header = Some(format!("Bearer {}", token));
+
+    let response = make_request(
+        Some(&client),
+        "post",
+        "/api/v1/admin/restart",
+        Some(json!({})),
+        auth_header.clone(),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    let json: Value = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(json["restarting"], false);
+    let confirmation_token = json["confirmation_token"].as_str().unwrap().to_string();
+    assert!(!shutdown_signal.is_shutdown());
+
+    make_request(
+        Some(&client),
+        "post",
+        "/api/v1/admin/restart",
+        Some(json!({ "confirmation_token": "wrong-token" })),
+        auth_header.clone(),
+        Some(Status::BadRequest),
+        Some(false),
+    )
+    .await;
+    assert!(!shutdown_signal.is_shutdown());
+
+    let response = make_request(
+        Some(&client),
+        "post",
+        "/api/v1/admin/restart",
+        Some(json!({ "confirmation_token": confirmation_token })),
+        auth_header,
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    let json: Value = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(json["restarting"], true);
+    assert!(shutdown_signal.is_shutdown());
+    assert_eq!(shutdown_signal.reason(), Some(ShutdownReason::Restart));
+}
+
+#[rocket::async_test]
+async fn test_restart_requires_admin() {
+    let client = create_test_client(Some("admin_route_restart_forbidden")).await;
+    let admin_token = create_and_login_user(&client, "admin", "password123", true, None)
+        .await
+        .expect("Expected admin to be able to log in");
+    make_request(
+        Some(&client),
+        "post",
+        "/create_user",
+        Some(json!({
+            "username": "viewer",
+            "password": "password123",
+            "admin": false
+        })),
+        Some(format!("Bearer {}", admin_token)),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    let viewer_token = login_user(&client, "viewer", "password123", Some(Status::Ok))
+        .await
+        .expect("Expected viewer to be able to log in");
+
+    make_request(
+        Some(&client),
+        "post",
+        "/api/v1/admin/restart",
+        Some(json!({})),
+        Some(format!("Bearer {}", viewer_token)),
+        Some(Status::Forbidden),
+        Some(false),
+    )
+    .await;
+}
+
+#[rocket::async_test]
+async fn test_maintenance_mode_blocks_writes_and_allows_reads() {
+    let client = create_test_client(Some("admin_route_maintenance")).await;
+    let token = create_and_login_user(&client, "admin", "password123", true, None)
+        .await
+        .expect("Expected admin to be able to log in");
+    let auth_header = Some(format!("Bearer {}", token));
+
+    let response = make_request(
+        Some(&client),
+        "put",
+        "/api/v1/admin/maintenance",
+        Some(json!({ "enabled": true })),
+        auth_header.clone(),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    let json: Value = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(json["enabled"], true);
+
+    make_request(
+        Some(&client),
+        "post",
+        "/create_user",
+        Some(json!({
+            "username": "viewer",
+            "password": "password123",
+            "admin": false
+        })),
+        auth_header.clone(),
+        Some(Status::ServiceUnavailable),
+        Some(false),
+    )
+    .await;
+    make_request(
+        Some(&client),
+        "delete",
+        "/api/v1/settings/libraries/99",
+        None,
+        auth_header.clone(),
+        Some(Status::ServiceUnavailable),
+        Some(false),
+    )
+    .await;
+
+    // Reads and logins keep working during maintenance.
+    login_user(&client, "admin", "password123", Some(Status::Ok))
+        .await
+        .expect("Expected login to keep working during maintenance");
+    make_request(
+        Some(&client),
+        "get",
+        "/api/v1/users",
+        None,
+        auth_header.clone(),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    let response = make_request(
+        Some(&client),
+        "get",
+        "/api/v1/admin/maintenance",
+        None,
+        auth_header.clone(),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    let json: Value = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(json["enabled"], true);
+
+    make_request(
+        Some(&client),
+        "put",
+        "/api/v1/admin/maintenance",
+        Some(json!({ "enabled": false })),
+        auth_header.clone(),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    make_request(
+        Some(&client),
+        "post",
+        "/create_user",
+        Some(json!({
+            "username": "viewer",
+            "password": "password123",
+            "admin": false
+        })),
+        auth_header,
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+}
+
+#[rocket::async_test]
+async fn test_maintenance_mode_blocks_library_scans() {
+    let client = create_test_client(Some("admin_route_maintenance_scan")).await;
+    let token = create_and_login_user(&client, "admin", "password123", true, None)
+        .await
+        .expect("Expected admin to be able to log in");
+    let auth_header = Some(format!("Bearer {}", token));
+
+    make_request(
+        Some(&client),
+        "put",
+        "/api/v1/admin/maintenance",
+        Some(json!({ "enabled": true })),
+        auth_header.clone(),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    make_request(
+        Some(&client),
+        "post",
+        "/api/v1/libraries/99/scan",
+        None,
+        auth_header.clone(),
+        Some(Status::ServiceUnavailable),
+        Some(false),
+    )
+    .await;
+    make_request(
+        Some(&client),
+        "post",
+        "/api/v1/settings/metadata-cache/clear",
+        None,
+        auth_header.clone(),
+        Some(Status::ServiceUnavailable),
+        Some(false),
+    )
+    .await;
+
+    // Without maintenance the scan reaches the route and reports the unknown library.
+    make_request(
+        Some(&client),
+        "put",
+        "/api/v1/admin/maintenance",
+        Some(json!({ "enabled": false })),
+        auth_header.clone(),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    make_request(
+        Some(&client),
+        "post",
+        "/api/v1/libraries/99/scan",
+        None,
+        auth_header,
+        Some(Status::NotFound),
+        Some(false),
+    )
+    .await;
+}
+
+#[rocket::async_test]
+async fn test_maintenance_mode_requires_admin() {
+    let client = create_test_client(Some("admin_route_maintenance_forbidden")).await;
+    let admin_token = create_and_login_user(&client, "admin", "password123", true, None)
+        .await
+        .expect("Expected admin to be able to log in");
+    make_request(
+        Some(&client),
+        "post",
+        "/create_user",
+        Some(json!({
+            "username": "viewer",
+            "password": "password123",
+            "admin": false
+        })),
+        Some(format!("Bearer {}", admin_token)),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    let viewer_token = login_user(&client, "viewer", "password123", Some(Status::Ok))
+        .await
+        .expect("Expected viewer to be able to log in");
+
+    make_request(
+        Some(&client),
+        "put",
+        "/api/v1/admin/maintenance",
+        Some(json!({ "enabled": true })),
+        Some(format!("Bearer {}", viewer_token)),
+        Some(Status::Forbidden),
+        Some(false),
+    )
+    .await;
+}
@@ -1,6 +1,12 @@
 // lib imports
-use rocket::http::Status;
-use serde_json::json;
+use rocket::http::{
+    ContentType,
+    Status,
+};
+use serde_json::{
+    Value,
+    json,
+};
 
 // test imports
 use crate::test_utils::{
@@ -130,3 +136,61 @@ async fn test_user_info_endpoint() {
     // The expires_at should be a number (from claims.exp)
     assert!(user_info["expires_at"].is_number());
 }
+
+#[rocket::async_test]
+async fn test_login_with_wrong_content_type_returns_json_error() {
+    let client = create_test_client(Some("auth_routes_login_wrong_content_type")).await;
+
+    let response = client
+        .post("/login")
+        .header(ContentType::Form)
+        .body("username=admin&password=password123")
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::UnsupportedMediaType);
+
+    let json: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    assert_eq!(json["status"], 415);
+    assert_eq!(json["error"], "unsupported_media_type");
+    assert!(
+        json["message"]
+            .as_str()
+            .unwrap()
+            .contains("Content-Type: application/json")
+    );
+}
+
+#[rocket::async_test]
+async fn test_create_user_without_content_type_returns_json_error() {
+    let client = create_test_client(Some("auth_routes_create_user_no_content_type")).await;
+
+    let response = client
+        .post("/create_user")
+        .body(r#"{"username":"admin","password":"password123","admin":true}"#)
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::UnsupportedMediaType);
+
+    let json: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    assert_eq!(json["error"], "unsupported_media_type");
+}
+
+#[rocket::async_test]
+async fn test_login_with_mismatched_json_returns_json_error() {
+    let client = create_test_client(Some("auth_routes_login_mismatched_json")).await;
+
+    let response = make_request(
+        Some(&client),
+        "post",
+        "/login",
+        Some(json!({ "username": "admin" })),
+        None,
+        Some(Status::UnprocessableEntity),
+        Some(false),
+    )
+    .await;
+
+    let json: Value = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(json["error"], "unprocessable_entity");
+    assert!(json["message"].as_str().unwrap().contains("/login"));
+}
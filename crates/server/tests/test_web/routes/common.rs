@@ -1,8 +1,13 @@
 // lib imports
 use rocket::http::Status;
+use serde_json::{
+    Value,
+    json,
+};
 
 // test imports
 use crate::test_utils::{
+    create_and_login_user,
     create_test_client,
     make_request,
 };
@@ -73,3 +78,127 @@ async fn test_root_route_serves_built_asset_when_available() {
         .await;
     }
 }
+
+#[rocket::async_test]
+async fn test_health_route() {
+    let response = make_request(
+        None,
+        "get",
+        "/health",
+        None,
+        None,
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+
+    let json: Value = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(json["status"], "ok");
+    assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+}
+
+#[rocket::async_test]
+async fn test_ready_route_queries_database() {
+    let client = create_test_client(Some("common_routes_ready")).await;
+
+    let response = make_request(
+        Some(&client),
+        "get",
+        "/ready",
+        None,
+        None,
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+
+    let json: Value = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(json["status"], "ok");
+    assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+}
+
+fn metric_value(
+    body: &str,
+    name: &str,
+) -> u64 {
+    body.lines()
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix(' '))
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| panic!("Expected metric {} in:\n{}", name, body))
+}
+
+#[rocket::async_test]
+async fn test_metrics_route_counts_logins() {
+    let client = create_test_client(Some("common_routes_metrics")).await;
+    let token = create_and_login_user(&client, "admin", "password123", true, None)
+        .await
+        .expect("Expected admin to be able to log in");
+    let auth_header = Some(format!("Bearer {}", token));
+
+    make_request(
+        Some(&client),
+        "get",
+        "/metrics",
+        None,
+        None,
+        Some(Status::Unauthorized),
+        Some(false),
+    )
+    .await;
+
+    let before = make_request(
+        Some(&client),
+        "get",
+        "/metrics",
+        None,
+        auth_header.clone(),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    let logins_before = metric_value(&before.body, "koko_logins_total");
+    assert!(before.body.contains("# TYPE koko_logins_total counter"));
+
+    make_request(
+        Some(&client),
+        "post",
+        "/login",
+        Some(json!({ "username": "admin", "password": "password123" })),
+        None,
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+
+    let after = make_request(
+        Some(&client),
+        "get",
+        "/metrics",
+        None,
+        auth_header,
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    assert!(metric_value(&after.body, "koko_logins_total") > logins_before);
+}
+
+#[rocket::async_test]
+async fn test_invalid_path_parameter_error_does_not_mention_json_body() {
+    let response = make_request(
+        None,
+        "post",
+        "/api/v1/libraries/abc/scan",
+        None,
+        None,
+        Some(Status::UnprocessableEntity),
+        Some(false),
+    )
+    .await;
+
+    let json: Value = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(json["error"], "unprocessable_entity");
+    let message = json["message"].as_str().unwrap();
+    assert!(message.contains("/api/v1/libraries/abc/scan"));
+    assert!(!message.contains("JSON"));
+}
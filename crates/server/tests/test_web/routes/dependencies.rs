@@ -29,9 +29,13 @@ async fn test_get_dependencies_route() {
     let body = response.body;
     let json: Value = serde_json::from_str(&body).unwrap();
 
-    assert!(json.is_array(), "Response is not a JSON array");
+    assert!(json.is_object(), "Response is not a JSON object");
+    assert!(
+        json["items"].is_array(),
+        "Response items is not a JSON array"
+    );
 
-    for item in json.as_array().unwrap() {
+    for item in json["items"].as_array().unwrap() {
         assert!(item.is_object(), "Array item is not a JSON object");
         let obj = item.as_object().unwrap();
         assert!(
@@ -48,3 +52,36 @@ async fn test_get_dependencies_route() {
         );
     }
 }
+
+#[rocket::async_test]
+async fn test_get_dependencies_route_pagination() {
+    let response: TestResponse = make_request(
+        None,
+        "get",
+        "/dependencies?limit=1&offset=1",
+        None,
+        None,
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+
+    let json: Value = serde_json::from_str(&response.body).unwrap();
+    let mut keys = json
+        .as_object()
+        .expect("Response is not a JSON object")
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    keys.sort();
+    assert_eq!(
+        keys,
+        vec![
+            "items", "limit", "offset", "total"
+        ]
+    );
+    assert_eq!(json["limit"], 1);
+    assert_eq!(json["offset"], 1);
+    assert!(json["items"].as_array().unwrap().len() <= 1);
+    assert!(json["total"].as_u64().unwrap() >= 1);
+}
@@ -6,7 +6,9 @@ use rocket::serde::json::{
 };
 
 use crate::test_utils::{
+    create_and_login_user,
     create_test_client,
+    login_user,
     make_request,
 };
 use koko::globals;
@@ -31,6 +33,56 @@ async fn test_get_settings_route() {
     assert!(json.get("settings_path").is_some());
 }
 
+#[rocket::async_test]
+async fn test_update_settings_requires_admin() {
+    let client = create_test_client(Some("settings_route_update_requires_admin")).await;
+    let admin_token = create_and_login_user(&client, "admin", "password123", true, None)
+        .await
+        .expect("Expected admin to be able to log in");
+    make_request(
+        Some(&client),
+        "post",
+        "/create_user",
+        Some(json!({ "username": "viewer", "password": "password123", "admin": false })),
+        Some(format!("Bearer {}", admin_token)),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    let viewer_token = login_user(&client, "viewer", "password123", Some(Status::Ok))
+        .await
+        .expect("Expected viewer to be able to log in");
+
+    let response = make_request(
+        Some(&client),
+        "get",
+        "/api/v1/settings",
+        None,
+        None,
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    let mut settings_json: Value = serde_json::from_str(&response.body).unwrap();
+    settings_json["settings"]["api"]["bcrypt_cost"] = json!(31);
+
+    for (auth_header, expected_status) in [
+        (None, Status::Unauthorized),
+        (Some(format!("Bearer {}", viewer_token)), Status::Forbidden),
+    ] {
+        make_request(
+            Some(&client),
+            "put",
+            "/api/v1/settings",
+            Some(settings_json["settings"].clone()),
+            auth_header,
+            Some(expected_status),
+            Some(false),
+        )
+        .await;
+    }
+}
+
 #[rocket::async_test]
 async fn test_add_and_remove_library_routes() {
     let client = create_test_client(Some("settings_route_add_remove")).await;
@@ -158,6 +210,10 @@ async fn test_add_library_route_allows_duplicate_root_paths() {
 #[rocket::async_test]
 async fn test_update_settings_with_empty_libraries_does_not_delete_catalog() {
     let client = create_test_client(Some("settings_route_update_preserves_libraries")).await;
+    let token = create_and_login_user(&client, "admin", "password123", true, None)
+        .await
+        .expect("Expected admin to be able to log in");
+    let auth_header = Some(format!("Bearer {}", token));
 
     make_request(
         Some(&client),
@@ -197,7 +253,7 @@ async fn test_update_settings_with_empty_libraries_does_not_delete_catalog() {
         "put",
         "/api/v1/settings",
         Some(settings_json["settings"].clone()),
-        None,
+        auth_header.clone(),
         Some(Status::Ok),
         Some(false),
     )
@@ -235,6 +291,10 @@ async fn test_update_settings_with_empty_libraries_does_not_delete_catalog() {
 async fn test_update_settings_with_partial_libraries_preserves_omitted_catalog_rows() {
     let client =
         create_test_client(Some("settings_route_update_preserves_omitted_libraries")).await;
+    let token = create_and_login_user(&client, "admin", "password123", true, None)
+        .await
+        .expect("Expected admin to be able to log in");
+    let auth_header = Some(format!("Bearer {}", token));
 
     for (name, kind) in [
         ("Movies", "movies"),
@@ -280,7 +340,7 @@ async fn test_update_settings_with_partial_libraries_preserves_omitted_catalog_r
         "put",
         "/api/v1/settings",
         Some(settings_json["settings"].clone()),
-        None,
+        auth_header.clone(),
         Some(Status::Ok),
         Some(false),
     )
@@ -387,3 +447,38 @@ async fn test_get_logs_route_filters_and_normalizes_paths() {
         "rocket-0.5.1/src/server.rs"
     );
 }
+
+#[rocket::async_test]
+async fn test_check_certificates_route_requires_admin_and_reports_pair() {
+    let client = create_test_client(Some("settings_route_check_certs")).await;
+
+    make_request(
+        Some(&client),
+        "get",
+        "/api/v1/debug/certs",
+        None,
+        None,
+        Some(Status::Unauthorized),
+        Some(false),
+    )
+    .await;
+
+    let token = create_and_login_user(&client, "admin", "password123", true, None)
+        .await
+        .expect("Expected admin to be able to log in");
+    let response = make_request(
+        Some(&client),
+        "get",
+        "/api/v1/debug/certs",
+        None,
+        Some(format!("Bearer {}", token)),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+
+    let json: Value = serde_json::from_str(&response.body).unwrap();
+    assert!(json["cert_path"].as_str().is_some());
+    assert!(json["key_path"].as_str().is_some());
+    assert_eq!(json["valid"], json!(json["certificate"].is_object()));
+}
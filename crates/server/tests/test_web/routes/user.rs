@@ -8,6 +8,7 @@ use rstest::rstest;
 
 // test imports
 use crate::test_utils::{
+    create_and_login_user,
     create_test_client,
     create_test_user,
     login_user,
@@ -38,6 +39,58 @@ async fn test_create_first_user_scenarios(
     assert_eq!(body, "User created");
 }
 
+#[rstest]
+#[case("leading_zero", "0123", Status::Ok)]
+#[case("six_digits", "123456", Status::Ok)]
+#[case("signed", "+123", Status::BadRequest)]
+#[case("leading_space", " 1234", Status::BadRequest)]
+#[case("too_short", "123", Status::BadRequest)]
+#[case("too_long", "1234567", Status::BadRequest)]
+#[case("non_digit", "12a4", Status::BadRequest)]
+#[test_attr(rocket::async_test)]
+async fn test_create_user_pin_validation(
+    #[case] name: &str,
+    #[case] pin: &str,
+    #[case] expected_status: Status,
+) {
+    let client = create_test_client(Some(&format!("user_routes_pin_{}", name))).await;
+
+    let (status, _) = create_test_user(
+        &client,
+        "admin",
+        "password123",
+        true,
+        Some(pin),
+        Some(expected_status),
+    )
+    .await;
+    assert_eq!(status, expected_status);
+}
+
+#[rstest]
+#[case("max_length", 64, Status::Ok)]
+#[case("too_long", 65, Status::BadRequest)]
+#[test_attr(rocket::async_test)]
+async fn test_create_user_username_length(
+    #[case] name: &str,
+    #[case] length: usize,
+    #[case] expected_status: Status,
+) {
+    let client = create_test_client(Some(&format!("user_routes_username_{}", name))).await;
+    let username = "u".repeat(length);
+
+    let (status, _) = create_test_user(
+        &client,
+        &username,
+        "password123",
+        true,
+        None,
+        Some(expected_status),
+    )
+    .await;
+    assert_eq!(status, expected_status);
+}
+
 #[rocket::async_test]
 async fn test_create_user_requires_auth() {
     let client = create_test_client(Some("user_routes_requires_auth")).await;
@@ -272,9 +325,355 @@ async fn test_cannot_demote_last_admin() {
             "remove_profile_image": true
         })),
         Some(format!("Bearer {}", token)),
-        Some(Status::BadRequest),
+        Some(Status::Conflict),
+        Some(false),
+    )
+    .await;
+    assert_eq!(response.status, Status::Conflict);
+}
+
+#[rocket::async_test]
+async fn test_demoting_admin_takes_effect_immediately() {
+    let client = create_test_client(Some("user_routes_demote_cache")).await;
+
+    let owner_token = create_and_login_user(&client, "owner", "password123", true, None)
+        .await
+        .expect("Expected owner to be able to log in");
+    let owner_header = Some(format!("Bearer {}", owner_token));
+    make_request(
+        Some(&client),
+        "post",
+        "/create_user",
+        Some(json!({
+            "username": "second-admin",
+            "password": "password123",
+            "admin": true
+        })),
+        owner_header.clone(),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    let second_token = login_user(&client, "second-admin", "password123", Some(Status::Ok))
+        .await
+        .expect("Expected second admin to be able to log in");
+    let second_header = Some(format!("Bearer {}", second_token));
+
+    // Prime the admin status cache for the second admin.
+    make_request(
+        Some(&client),
+        "get",
+        "/admin_test",
+        None,
+        second_header.clone(),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+
+    make_request(
+        Some(&client),
+        "put",
+        "/api/v1/users/2",
+        Some(json!({
+            "username": "second-admin",
+            "admin": false
+        })),
+        owner_header,
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+
+    make_request(
+        Some(&client),
+        "get",
+        "/admin_test",
+        None,
+        second_header,
+        Some(Status::Forbidden),
+        Some(false),
+    )
+    .await;
+}
+
+#[rstest]
+#[case(false, true)]
+#[case(true, false)]
+#[test_attr(rocket::async_test)]
+async fn test_patch_user_admin_promotes_and_demotes(
+    #[case] initial_admin: bool,
+    #[case] next_admin: bool,
+) {
+    let client = create_test_client(Some(&format!(
+        "user_routes_patch_admin_{}_{}",
+        initial_admin, next_admin
+    )))
+    .await;
+
+    let token = create_and_login_user(&client, "owner", "password123", true, None)
+        .await
+        .expect("Expected owner to be able to log in");
+    let auth_header = Some(format!("Bearer {}", token));
+    make_request(
+        Some(&client),
+        "post",
+        "/create_user",
+        Some(json!({
+            "username": "member",
+            "password": "password123",
+            "admin": initial_admin
+        })),
+        auth_header.clone(),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+
+    let response = make_request(
+        Some(&client),
+        "patch",
+        "/api/v1/users/2",
+        Some(json!({ "admin": next_admin })),
+        auth_header,
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+
+    let json: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(json["username"], "member");
+    assert_eq!(json["admin"], next_admin);
+}
+
+#[rocket::async_test]
+async fn test_patch_user_admin_rejects_demoting_last_admin() {
+    let client = create_test_client(Some("user_routes_patch_last_admin")).await;
+
+    let token = create_and_login_user(&client, "owner", "password123", true, None)
+        .await
+        .expect("Expected owner to be able to log in");
+
+    make_request(
+        Some(&client),
+        "patch",
+        "/api/v1/users/1",
+        Some(json!({ "admin": false })),
+        Some(format!("Bearer {}", token)),
+        Some(Status::Conflict),
+        Some(false),
+    )
+    .await;
+
+    make_request(
+        Some(&client),
+        "patch",
+        "/api/v1/users/99",
+        Some(json!({ "admin": true })),
+        Some(format!("Bearer {}", token)),
+        Some(Status::NotFound),
+        Some(false),
+    )
+    .await;
+}
+
+#[rocket::async_test]
+async fn test_list_users_returns_pagination_metadata() {
+    let client = create_test_client(Some("user_routes_list_paginated")).await;
+
+    let token = create_and_login_user(&client, "admin", "password123", true, None)
+        .await
+        .expect("Expected admin to be able to log in");
+    let auth_header = Some(format!("Bearer {}", token));
+
+    for username in ["viewer-a", "viewer-b"] {
+        make_request(
+            Some(&client),
+            "post",
+            "/create_user",
+            Some(json!({
+                "username": username,
+                "password": "password123",
+                "admin": false
+            })),
+            auth_header.clone(),
+            Some(Status::Ok),
+            Some(false),
+        )
+        .await;
+    }
+
+    let response = make_request(
+        Some(&client),
+        "get",
+        "/api/v1/users?limit=2&offset=1",
+        None,
+        auth_header,
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+
+    let json: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(json["total"], 3);
+    assert_eq!(json["limit"], 2);
+    assert_eq!(json["offset"], 1);
+    let usernames = json["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|user| user["username"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(usernames, vec!["viewer-a", "viewer-b"]);
+}
+
+#[rocket::async_test]
+async fn test_list_users_omits_credentials() {
+    let client = create_test_client(Some("user_routes_list_credentials")).await;
+
+    let token = create_and_login_user(&client, "admin", "password123", true, Some("1234"))
+        .await
+        .expect("Expected admin to be able to log in");
+
+    let response = make_request(
+        Some(&client),
+        "get",
+        "/api/v1/users",
+        None,
+        Some(format!("Bearer {}", token)),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+
+    let json: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+    let user = &json["items"][0];
+    assert_eq!(user["username"], "admin");
+    assert_eq!(user["admin"], true);
+    assert!(user.get("password").is_none());
+    assert!(user.get("pin").is_none());
+}
+
+#[rocket::async_test]
+async fn test_admin_can_delete_user() {
+    let client = create_test_client(Some("user_routes_delete")).await;
+
+    let token = create_and_login_user(&client, "admin", "password123", true, None)
+        .await
+        .expect("Expected admin to be able to log in");
+    let auth_header = Some(format!("Bearer {}", token));
+    make_request(
+        Some(&client),
+        "post",
+        "/create_user",
+        Some(json!({ "username": "viewer", "password": "password123", "admin": false })),
+        auth_header.clone(),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    let viewer_token = login_user(&client, "viewer", "password123", Some(Status::Ok))
+        .await
+        .expect("Expected viewer to be able to log in");
+
+    make_request(
+        Some(&client),
+        "delete",
+        "/api/v1/users/1",
+        None,
+        Some(format!("Bearer {}", viewer_token)),
+        Some(Status::Forbidden),
+        Some(false),
+    )
+    .await;
+    make_request(
+        Some(&client),
+        "delete",
+        "/api/v1/users/2",
+        None,
+        auth_header.clone(),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    make_request(
+        Some(&client),
+        "delete",
+        "/api/v1/users/2",
+        None,
+        auth_header.clone(),
+        Some(Status::NotFound),
+        Some(false),
+    )
+    .await;
+    login_user(&client, "viewer", "password123", Some(Status::Unauthorized))
+        .await
+        .expect_err("Deleted user should not be able to log in");
+
+    let response = make_request(
+        Some(&client),
+        "get",
+        "/api/v1/users",
+        None,
+        auth_header,
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    let json: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(json["total"], 1);
+}
+
+#[rocket::async_test]
+async fn test_cannot_delete_last_admin() {
+    let client = create_test_client(Some("user_routes_delete_last_admin")).await;
+
+    let token = create_and_login_user(&client, "owner", "password123", true, None)
+        .await
+        .expect("Expected owner to be able to log in");
+    let auth_header = Some(format!("Bearer {}", token));
+
+    make_request(
+        Some(&client),
+        "delete",
+        "/api/v1/users/1",
+        None,
+        auth_header.clone(),
+        Some(Status::Conflict),
+        Some(false),
+    )
+    .await;
+
+    make_request(
+        Some(&client),
+        "post",
+        "/create_user",
+        Some(json!({ "username": "second", "password": "password123", "admin": true })),
+        auth_header.clone(),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    make_request(
+        Some(&client),
+        "delete",
+        "/api/v1/users/1",
+        None,
+        auth_header.clone(),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+
+    // The deleted admin's token no longer grants admin access.
+    make_request(
+        Some(&client),
+        "get",
+        "/api/v1/users",
+        None,
+        auth_header,
+        Some(Status::Forbidden),
         Some(false),
     )
     .await;
-    assert_eq!(response.status, Status::BadRequest);
 }
@@ -1,7 +1,14 @@
 //! Integration tests for authentication routes.
 
 // lib imports
-use rocket::http::Status;
+use diesel::RunQueryDsl;
+use diesel::sql_types::Text;
+use rocket::futures::future::join_all;
+use rocket::http::{
+    ContentType,
+    Header,
+    Status,
+};
 use rstest::rstest;
 use serde_json::json;
 
@@ -184,6 +191,56 @@ async fn test_jwt_protected_routes_with_invalid_token(#[case] auth_header: &str)
     // Status assertion is now handled by expected_status parameter
 }
 
+#[rstest]
+#[case("lowercase", "bearer")]
+#[case("uppercase", "BEARER")]
+#[case("mixed_case", "BeArEr")]
+#[test_attr(rocket::async_test)]
+async fn test_jwt_bearer_scheme_is_case_insensitive(
+    #[case] name: &str,
+    #[case] scheme: &str,
+) {
+    let client = create_test_client(Some(&format!("auth_scheme_{}", name))).await;
+    let token = create_and_login_user(&client, "jwtuser", "jwtpass123", false, None)
+        .await
+        .expect("Should create and login user successfully");
+
+    make_request(
+        Some(&client),
+        "get",
+        "/jwt_test",
+        None,
+        Some(format!("{} {}", scheme, token)),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+}
+
+#[rocket::async_test]
+async fn test_jwt_duplicate_authorization_headers_use_first_valid_token() {
+    let client = create_test_client(Some("auth_duplicate_header")).await;
+    let token = create_and_login_user(&client, "jwtuser", "jwtpass123", false, None)
+        .await
+        .expect("Should create and login user successfully");
+
+    let response = client
+        .get("/jwt_test")
+        .header(Header::new("Authorization", "Bearer invalid_token"))
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+
+    let response = client
+        .get("/jwt_test")
+        .header(Header::new("Authorization", "Bearer invalid_token"))
+        .header(Header::new("Authorization", "Basic dXNlcjpwYXNz"))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Unauthorized);
+}
+
 #[rocket::async_test]
 async fn test_jwt_protected_route_with_valid_token() {
     let client = create_test_client(Some("auth_valid_token")).await;
@@ -384,3 +441,299 @@ async fn test_create_user_with_invalid_pin() {
     )
     .await;
 }
+
+#[rocket::async_test]
+async fn test_concurrent_first_user_creation_has_single_winner() {
+    let client = create_test_client(Some("auth_concurrent_first_user")).await;
+
+    let requests = (0..16).map(|index| {
+        client
+            .post("/create_user")
+            .header(ContentType::JSON)
+            .body(
+                json!({
+                    "username": format!("racer{}", index),
+                    "password": "password123",
+                    "admin": false
+                })
+                .to_string(),
+            )
+            .dispatch()
+    });
+    let statuses = join_all(requests)
+        .await
+        .into_iter()
+        .map(|response| response.status())
+        .collect::<Vec<_>>();
+
+    let created = statuses
+        .iter()
+        .filter(|status| **status == Status::Ok)
+        .count();
+    assert_eq!(
+        created, 1,
+        "Expected exactly one first user, got {statuses:?}"
+    );
+    assert!(
+        statuses
+            .iter()
+            .all(|status| *status == Status::Ok || *status == Status::Unauthorized),
+        "Unexpected statuses: {statuses:?}"
+    );
+}
+
+#[rocket::async_test]
+async fn test_concurrent_logins_and_user_creation() {
+    let client = create_test_client(Some("auth_concurrent_logins")).await;
+    let admin_token = create_and_login_user(&client, "admin", "password123", true, None)
+        .await
+        .expect("Expected admin to be able to log in");
+
+    // Keep the load within the default pool (four connections per worker) so slow CI
+    // runners don't time out waiting for a connection while bcrypt runs.
+    let logins = (0..4).map(|_| {
+        client
+            .post("/login")
+            .header(ContentType::JSON)
+            .body(json!({ "username": "admin", "password": "password123" }).to_string())
+            .dispatch()
+    });
+    let creates = (0..4).map(|index| {
+        client
+            .post("/create_user")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", admin_token),
+            ))
+            .body(
+                json!({
+                    "username": format!("viewer{}", index),
+                    "password": "password123",
+                    "admin": false
+                })
+                .to_string(),
+            )
+            .dispatch()
+    });
+    let (logins, creates) = rocket::tokio::join!(join_all(logins), join_all(creates));
+
+    assert!(
+        logins
+            .iter()
+            .all(|response| response.status() == Status::Ok)
+    );
+    assert!(
+        creates
+            .iter()
+            .all(|response| response.status() == Status::Ok)
+    );
+
+    let response = make_request(
+        Some(&client),
+        "get",
+        "/api/v1/users",
+        None,
+        Some(format!("Bearer {}", admin_token)),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    let users: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(users["total"], 5);
+}
+
+#[rocket::async_test]
+async fn test_refresh_token_issues_new_access_token() {
+    let client = create_test_client(Some("auth_refresh")).await;
+    create_test_user(
+        &client,
+        "refresher",
+        "refreshpass",
+        false,
+        None,
+        Some(Status::Ok),
+    )
+    .await;
+
+    let response = make_request(
+        Some(&client),
+        "post",
+        "/login",
+        Some(json!({ "username": "refresher", "password": "refreshpass" })),
+        None,
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+    let access_token = body["token"].as_str().unwrap();
+    let refresh_token = body["refresh_token"].as_str().unwrap();
+
+    // A refresh token is not an access token, and vice versa.
+    make_request(
+        Some(&client),
+        "get",
+        "/jwt_test",
+        None,
+        Some(format!("Bearer {}", refresh_token)),
+        Some(Status::Unauthorized),
+        Some(false),
+    )
+    .await;
+    make_request(
+        Some(&client),
+        "post",
+        "/refresh",
+        Some(json!({ "refresh_token": access_token })),
+        None,
+        Some(Status::Unauthorized),
+        Some(false),
+    )
+    .await;
+
+    let response = make_request(
+        Some(&client),
+        "post",
+        "/refresh",
+        Some(json!({ "refresh_token": refresh_token })),
+        None,
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+    assert!(body["refresh_token"].is_string());
+    make_request(
+        Some(&client),
+        "get",
+        "/jwt_test",
+        None,
+        Some(format!("Bearer {}", body["token"].as_str().unwrap())),
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+}
+
+#[rocket::async_test]
+async fn test_refresh_token_revoked_after_password_change() {
+    let client = create_test_client(Some("auth_refresh_revoked")).await;
+    create_test_user(
+        &client,
+        "revoked",
+        "oldpassword",
+        false,
+        None,
+        Some(Status::Ok),
+    )
+    .await;
+
+    let response = make_request(
+        Some(&client),
+        "post",
+        "/login",
+        Some(json!({ "username": "revoked", "password": "oldpassword" })),
+        None,
+        Some(Status::Ok),
+        Some(false),
+    )
+    .await;
+    let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+    let refresh_token = body["refresh_token"].as_str().unwrap().to_string();
+
+    let db = koko::db::DbConn::get_one(client.rocket())
+        .await
+        .expect("database connection");
+    let new_password = koko::auth::hash_password("newpassword").unwrap();
+    db.run(move |conn| {
+        diesel::sql_query("UPDATE users SET password = ? WHERE username = 'revoked'")
+            .bind::<Text, _>(new_password)
+            .execute(conn)
+    })
+    .await
+    .expect("update password");
+
+    make_request(
+        Some(&client),
+        "post",
+        "/refresh",
+        Some(json!({ "refresh_token": refresh_token })),
+        None,
+        Some(Status::Unauthorized),
+        Some(false),
+    )
+    .await;
+}
+
+#[rstest]
+#[case("1234", Status::Ok)]
+#[case("4321", Status::Unauthorized)]
+#[case("password123", Status::Unauthorized)]
+#[test_attr(rocket::async_test)]
+async fn test_login_with_pin(
+    #[case] pin: &str,
+    #[case] expected_status: Status,
+) {
+    let client = create_test_client(Some(&format!("auth_login_pin_{}", pin))).await;
+    create_test_user(
+        &client,
+        "pinuser",
+        "password123",
+        false,
+        Some("1234"),
+        Some(Status::Ok),
+    )
+    .await;
+
+    let response = make_request(
+        Some(&client),
+        "post",
+        "/login_pin",
+        Some(json!({ "username": "pinuser", "pin": pin })),
+        None,
+        Some(expected_status),
+        Some(false),
+    )
+    .await;
+    if expected_status == Status::Ok {
+        let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+        make_request(
+            Some(&client),
+            "get",
+            "/jwt_test",
+            None,
+            Some(format!("Bearer {}", body["token"].as_str().unwrap())),
+            Some(Status::Ok),
+            Some(false),
+        )
+        .await;
+    }
+}
+
+#[rocket::async_test]
+async fn test_login_with_pin_requires_pin() {
+    let client = create_test_client(Some("auth_login_pin_unset")).await;
+    create_test_user(
+        &client,
+        "nopin",
+        "password123",
+        false,
+        None,
+        Some(Status::Ok),
+    )
+    .await;
+
+    for credential in ["", "1234", "password123"] {
+        make_request(
+            Some(&client),
+            "post",
+            "/login_pin",
+            Some(json!({ "username": "nopin", "pin": credential })),
+            None,
+            Some(Status::Unauthorized),
+            Some(false),
+        )
+        .await;
+    }
+}